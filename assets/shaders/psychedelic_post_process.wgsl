@@ -0,0 +1,66 @@
+// Fisheye, screen-space ripple, kaleidoscope and chromatic aberration over the tonemapped frame.
+// Driven by `PsychedelicPostProcess` in src/post_process.rs.
+
+#import bevy_core_pipeline::fullscreen_vertex_shader::FullscreenVertexOutput
+
+const TAU: f32 = 6.28318530718;
+
+struct PsychedelicPostProcess {
+    distortion: f32,
+    chromatic_aberration: f32,
+    fisheye: f32,
+    wave_amplitude: f32,
+    kaleidoscope: f32,
+    kaleidoscope_segments: f32,
+    intensity: f32,
+    time: f32,
+}
+
+@group(0) @binding(0) var screen_texture: texture_2d<f32>;
+@group(0) @binding(1) var texture_sampler: sampler;
+@group(0) @binding(2) var<uniform> settings: PsychedelicPostProcess;
+
+// Mirrors the image into wedges around the centre of the screen.
+fn kaleidoscope(uv: vec2<f32>) -> vec2<f32> {
+    let centered = uv - 0.5;
+    let radius = length(centered);
+    let segment = TAU / max(settings.kaleidoscope_segments, 1.0);
+
+    var angle = atan2(centered.y, centered.x) + settings.time * 0.1;
+    angle = angle - segment * floor(angle / segment);
+    angle = abs(angle - segment * 0.5);
+
+    return vec2<f32>(cos(angle), sin(angle)) * radius + 0.5;
+}
+
+// Barrel distortion, normalised so the corners stay pinned.
+fn fisheye(uv: vec2<f32>, strength: f32) -> vec2<f32> {
+    let centered = uv - 0.5;
+    let radius_squared = dot(centered, centered);
+    return 0.5 + centered * (1.0 + strength * radius_squared) / (1.0 + strength * 0.5);
+}
+
+@fragment
+fn fragment(in: FullscreenVertexOutput) -> @location(0) vec4<f32> {
+    var uv = in.uv;
+
+    if settings.kaleidoscope > 0.0 {
+        uv = mix(uv, kaleidoscope(uv), settings.kaleidoscope);
+    }
+
+    uv = fisheye(uv, settings.fisheye + settings.distortion);
+
+    let ripple = vec2<f32>(
+        sin(uv.y * 20.0 + settings.time * 2.0),
+        cos(uv.x * 20.0 + settings.time * 1.7),
+    );
+    uv += ripple * settings.wave_amplitude;
+
+    // Split the channels outward from the centre
+    let aberration = (uv - 0.5) * settings.chromatic_aberration;
+    let red = textureSample(screen_texture, texture_sampler, uv + aberration).r;
+    let green = textureSample(screen_texture, texture_sampler, uv).g;
+    let blue_alpha = textureSample(screen_texture, texture_sampler, uv - aberration).ba;
+
+    return vec4<f32>(red, green, blue_alpha);
+}
@@ -1,25 +1,273 @@
 use bevy::prelude::*;
 use bevy::input::mouse::MouseMotion;
 use bevy::window::{CursorGrabMode, PrimaryWindow};
-use crate::{GameCamera, GameConfig, GameState};
+use leafwing_input_manager::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::f32::consts::{PI, TAU};
+use crate::{
+    GameCamera, GameConfig, GameState,
+    controls::PlayerAction,
+    gamepad::*,
+    persistence::{load_ron, save_ron},
+    physics::*,
+    player::{sync_camera_to_player, PlayerModel},
+    post_process::PsychedelicPostProcess,
+    zombies::Zombie,
+};
+
+/// Where camera settings are saved between sessions.
+const CAMERA_SETTINGS_PATH: &str = "camera_settings.ron";
+
+/// Toggles the free-fly camera in dev builds.
+#[cfg(feature = "dev")]
+const FREE_FLY_TOGGLE_KEY: KeyCode = KeyCode::F4;
+#[cfg(feature = "dev")]
+const FREE_FLY_SPEED: f32 = 10.0;
+#[cfg(feature = "dev")]
+const FREE_FLY_SPRINT_MULTIPLIER: f32 = 3.0;
 
 pub struct CameraPlugin;
 
 impl Plugin for CameraPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Startup, setup_camera)
+        app.init_resource::<CameraMode>()
+            .init_resource::<CameraRigSettings>()
+            .insert_resource(load_ron::<CameraSettings>(CAMERA_SETTINGS_PATH).unwrap_or_default())
+            .add_systems(Startup, setup_camera)
             .add_systems(
                 Update,
                 (
+                    update_aim.before(gamepad_look),
+                    gamepad_look.before(mouse_look),
                     mouse_look,
                     handle_cursor_grab_ingame,
                     update_camera_effects,
+                    cycle_camera_mode,
+                    position_camera_rig
+                        .after(mouse_look)
+                        .after(sync_camera_to_player)
+                        .after(cycle_camera_mode),
+                    update_player_model_visibility.after(cycle_camera_mode),
                 ).run_if(in_state(GameState::InGame)),
             )
+            .add_systems(Update, orbit_game_over_camera.run_if(in_state(GameState::GameOver)))
             .add_systems(OnEnter(GameState::MainMenu), release_cursor)
             .add_systems(OnEnter(GameState::Paused), release_cursor)
+            .add_systems(OnEnter(GameState::PerkDraft), release_cursor)
             .add_systems(OnEnter(GameState::Settings), release_cursor)
-            .add_systems(OnEnter(GameState::GameOver), release_cursor);
+            .add_systems(OnEnter(GameState::GameOver), (release_cursor, enter_spectator_camera))
+            .add_systems(OnExit(GameState::GameOver), leave_spectator_camera);
+
+        #[cfg(feature = "dev")]
+        app.add_systems(
+            Update,
+            (toggle_free_fly, free_fly_camera.after(mouse_look)).run_if(in_state(GameState::InGame)),
+        );
+    }
+}
+
+/// Player-facing look and view options, edited on the camera settings page.
+#[derive(Resource, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CameraSettings {
+    pub fov_degrees: f32,
+    /// Radians per pixel of mouse movement.
+    pub sensitivity_x: f32,
+    pub sensitivity_y: f32,
+    pub invert_y: bool,
+    /// 0 is raw input; closer to 1 trails the mouse more smoothly.
+    pub smoothing: f32,
+    /// Scales look sensitivity while aiming down sights.
+    pub ads_sensitivity_multiplier: f32,
+    /// Fraction of the field of view kept while aiming down sights.
+    pub ads_zoom: f32,
+    /// How far up or down the camera can look, in degrees.
+    pub pitch_limit_degrees: f32,
+    /// Scales screen shake, head bob, landing dip and FOV kick; 0 turns them off.
+    pub camera_motion: f32,
+}
+
+impl Default for CameraSettings {
+    fn default() -> Self {
+        Self {
+            fov_degrees: 110.0,
+            sensitivity_x: 0.002,
+            sensitivity_y: 0.002,
+            invert_y: false,
+            smoothing: 0.0,
+            ads_sensitivity_multiplier: 0.6,
+            ads_zoom: 0.7,
+            pitch_limit_degrees: 86.0,
+            camera_motion: 1.0,
+        }
+    }
+}
+
+impl CameraSettings {
+    pub fn save(&self) {
+        save_ron(CAMERA_SETTINGS_PATH, self);
+    }
+
+    pub fn clamp_pitch(&self, pitch: f32) -> f32 {
+        let limit = self.pitch_limit_degrees.to_radians();
+        pitch.clamp(-limit, limit)
+    }
+
+    /// Field of view in degrees for an aim blend between hip fire (0) and fully aimed (1).
+    pub fn view_fov(&self, aim_blend: f32) -> f32 {
+        self.fov_degrees * (1.0 - (1.0 - self.ads_zoom) * aim_blend)
+    }
+
+    /// Look sensitivity scale for an aim blend, shared by mouse and stick.
+    pub fn aim_sensitivity(&self, aim_blend: f32) -> f32 {
+        1.0 + (self.ads_sensitivity_multiplier - 1.0) * aim_blend
+    }
+
+    /// 1 for normal vertical look, -1 when inverted.
+    pub fn pitch_sign(&self) -> f32 {
+        if self.invert_y { -1.0 } else { 1.0 }
+    }
+}
+
+/// A value on the camera settings page.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CameraSetting {
+    Fov,
+    SensitivityX,
+    SensitivityY,
+    InvertY,
+    Smoothing,
+    AdsSensitivity,
+}
+
+impl CameraSetting {
+    pub const ALL: [CameraSetting; 6] = [
+        CameraSetting::Fov,
+        CameraSetting::SensitivityX,
+        CameraSetting::SensitivityY,
+        CameraSetting::InvertY,
+        CameraSetting::Smoothing,
+        CameraSetting::AdsSensitivity,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            CameraSetting::Fov => "Field of View",
+            CameraSetting::SensitivityX => "Horizontal Sensitivity",
+            CameraSetting::SensitivityY => "Vertical Sensitivity",
+            CameraSetting::InvertY => "Invert Y",
+            CameraSetting::Smoothing => "Mouse Smoothing",
+            CameraSetting::AdsSensitivity => "Aim Sensitivity",
+        }
+    }
+
+    pub fn display_value(&self, settings: &CameraSettings) -> String {
+        match self {
+            CameraSetting::Fov => format!("{:.0}", settings.fov_degrees),
+            CameraSetting::SensitivityX => format!("{:.4}", settings.sensitivity_x),
+            CameraSetting::SensitivityY => format!("{:.4}", settings.sensitivity_y),
+            CameraSetting::InvertY => if settings.invert_y { "ON" } else { "OFF" }.to_string(),
+            CameraSetting::Smoothing if settings.smoothing <= 0.0 => "OFF".to_string(),
+            CameraSetting::Smoothing => format!("{:.2}", settings.smoothing),
+            CameraSetting::AdsSensitivity => format!("{:.2}x", settings.ads_sensitivity_multiplier),
+        }
+    }
+
+    /// Steps the setting up (`direction` 1) or down (-1) within its range; toggles flip either way.
+    pub fn adjust(&self, settings: &mut CameraSettings, direction: f32) {
+        match self {
+            CameraSetting::Fov => {
+                settings.fov_degrees = (settings.fov_degrees + direction * 5.0).clamp(60.0, 130.0);
+            }
+            CameraSetting::SensitivityX => {
+                settings.sensitivity_x = (settings.sensitivity_x + direction * 0.0005).clamp(0.0005, 0.01);
+            }
+            CameraSetting::SensitivityY => {
+                settings.sensitivity_y = (settings.sensitivity_y + direction * 0.0005).clamp(0.0005, 0.01);
+            }
+            CameraSetting::InvertY => settings.invert_y = !settings.invert_y,
+            CameraSetting::Smoothing => {
+                settings.smoothing = (settings.smoothing + direction * 0.1).clamp(0.0, 0.9);
+            }
+            CameraSetting::AdsSensitivity => {
+                settings.ads_sensitivity_multiplier = (settings.ads_sensitivity_multiplier + direction * 0.1).clamp(0.2, 1.5);
+            }
+        }
+    }
+}
+
+/// Where the game camera sits relative to the player.
+#[derive(Resource, Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CameraMode {
+    #[default]
+    FirstPerson,
+    /// Over the shoulder, pulled in when geometry gets between the camera and the player.
+    ThirdPerson,
+    /// Watches from outside play: the orbit behind the game over screen, later spectating in multiplayer.
+    /// Not part of the live-play cycle.
+    Spectator,
+    /// Detached from the player and flown with the movement keys.
+    #[cfg(feature = "dev")]
+    FreeFly,
+}
+
+impl CameraMode {
+    /// Next mode in the cycle bound to `PlayerAction::CycleCamera`.
+    pub fn next(&self) -> Self {
+        match self {
+            CameraMode::FirstPerson => CameraMode::ThirdPerson,
+            CameraMode::ThirdPerson => CameraMode::FirstPerson,
+            CameraMode::Spectator => CameraMode::FirstPerson,
+            #[cfg(feature = "dev")]
+            CameraMode::FreeFly => CameraMode::FirstPerson,
+        }
+    }
+
+    #[cfg(feature = "dev")]
+    pub fn follows_player(&self) -> bool {
+        *self != CameraMode::FreeFly
+    }
+
+    #[cfg(not(feature = "dev"))]
+    pub fn follows_player(&self) -> bool {
+        true
+    }
+}
+
+/// Run condition for systems that drive the player or pin the camera to them.
+pub fn camera_follows_player(camera_mode: Res<CameraMode>) -> bool {
+    camera_mode.follows_player()
+}
+
+#[derive(Resource)]
+pub struct CameraRigSettings {
+    /// Third-person pivot relative to the eye, in camera space.
+    pub shoulder_offset: Vec3,
+    pub boom_length: f32,
+    pub spectator_distance: f32,
+    /// Radius of the sphere swept along the boom to keep the camera out of walls.
+    pub collision_radius: f32,
+    /// How quickly the boom extends again once the obstruction is gone; it always retracts instantly.
+    pub boom_return_speed: f32,
+    /// Slow circle around the arena behind the game over screen.
+    pub game_over_orbit_distance: f32,
+    pub game_over_orbit_height: f32,
+    /// Radians per second.
+    pub game_over_orbit_speed: f32,
+}
+
+impl Default for CameraRigSettings {
+    fn default() -> Self {
+        Self {
+            shoulder_offset: Vec3::new(0.6, 0.3, 0.0),
+            boom_length: 3.0,
+            spectator_distance: 8.0,
+            collision_radius: 0.2,
+            boom_return_speed: 6.0,
+            game_over_orbit_distance: 20.0,
+            game_over_orbit_height: 8.0,
+            game_over_orbit_speed: 0.15,
+        }
     }
 }
 
@@ -30,6 +278,14 @@ pub struct FirstPersonCamera {
     pub distortion_strength: f32,
     pub chromatic_aberration: f32,
     pub time_accumulator: f32,
+    /// Current distance from the eye in third person and spectator, shortened by obstructions.
+    pub boom_length: f32,
+    /// The player's eye before the third-person boom and camera effects; weapons aim from here.
+    pub eye: Transform,
+    /// Eases from 0 to 1 while aiming down sights.
+    pub aim_blend: f32,
+    /// Smoothed mouse look, in pixels per second.
+    pub look_velocity: Vec2,
 }
 
 impl Default for FirstPersonCamera {
@@ -40,11 +296,15 @@ impl Default for FirstPersonCamera {
             distortion_strength: 0.0,
             chromatic_aberration: 0.0,
             time_accumulator: 0.0,
+            boom_length: 0.0,
+            eye: Transform::IDENTITY,
+            aim_blend: 0.0,
+            look_velocity: Vec2::ZERO,
         }
     }
 }
 
-fn setup_camera(mut commands: Commands) {
+fn setup_camera(mut commands: Commands, camera_settings: Res<CameraSettings>) {
     // Spawn the first-person camera
     commands.spawn((
         Camera3dBundle {
@@ -53,7 +313,7 @@ fn setup_camera(mut commands: Commands) {
                 ..default()
             },
             projection: Projection::Perspective(PerspectiveProjection {
-                fov: 110.0_f32.to_radians(), 
+                fov: camera_settings.fov_degrees.to_radians(),
                 ..default()
             }),
             transform: Transform::from_xyz(0.0, 1.8, 0.0), // Eye height
@@ -61,13 +321,15 @@ fn setup_camera(mut commands: Commands) {
         },
         GameCamera,
         FirstPersonCamera::default(),
+        PsychedelicPostProcess::default(),
     ));
 }
 
 fn mouse_look(
     mut mouse_motion: EventReader<MouseMotion>,
     mut camera_query: Query<(&mut Transform, &mut FirstPersonCamera), With<GameCamera>>,
-    config: Res<GameConfig>,
+    camera_settings: Res<CameraSettings>,
+    time: Res<Time>,
 ) {
     if let Ok((mut transform, mut camera)) = camera_query.get_single_mut() {
         let mut delta = Vec2::ZERO;
@@ -75,15 +337,24 @@ fn mouse_look(
             delta += motion.delta;
         }
 
+        // Smooth as a rate so the feel doesn't change with frame rate
+        let dt = time.delta_seconds();
+        if camera_settings.smoothing > 0.0 && dt > 0.0 {
+            let keep = camera_settings.smoothing.powf(dt * 60.0);
+            camera.look_velocity = camera.look_velocity * keep + (delta / dt) * (1.0 - keep);
+            delta = camera.look_velocity * dt;
+        }
+
         // Apply mouse sensitivity
-        delta *= config.mouse_sensitivity;
+        let aim_scale = camera_settings.aim_sensitivity(camera.aim_blend);
+        delta *= Vec2::new(camera_settings.sensitivity_x, camera_settings.sensitivity_y) * aim_scale;
 
         // Update yaw and pitch
         camera.yaw -= delta.x;
-        camera.pitch -= delta.y;
+        camera.pitch -= delta.y * camera_settings.pitch_sign();
 
         // Clamp pitch to prevent camera flipping
-        camera.pitch = camera.pitch.clamp(-1.5, 1.5);
+        camera.pitch = camera_settings.clamp_pitch(camera.pitch);
 
         // Apply rotation
         transform.rotation = Quat::from_axis_angle(Vec3::Y, camera.yaw)
@@ -91,12 +362,117 @@ fn mouse_look(
     }
 }
 
+fn gamepad_look(
+    action_state: Res<ActionState<PlayerAction>>,
+    gamepad_config: Res<GamepadConfig>,
+    input_device: Res<InputDevice>,
+    mut camera_query: Query<(&Transform, &mut FirstPersonCamera), With<GameCamera>>,
+    zombie_query: Query<(&Transform, &Collider), (With<Zombie>, Without<GameCamera>)>,
+    spatial_query: SpatialQuery,
+    camera_settings: Res<CameraSettings>,
+    time: Res<Time>,
+) {
+    let Ok((camera_transform, mut camera)) = camera_query.get_single_mut() else {
+        return;
+    };
+    let dt = time.delta_seconds();
+
+    let look = apply_stick_response(
+        action_state.axis_pair(&PlayerAction::Look),
+        gamepad_config.look_deadzone,
+        gamepad_config.look_curve,
+    );
+    let is_moving = action_state.axis_pair(&PlayerAction::Move).length() > gamepad_config.move_deadzone;
+    let mut look_speed = gamepad_config.look_speed * camera_settings.aim_sensitivity(camera.aim_blend);
+
+    if gamepad_config.aim_assist && *input_device == InputDevice::Gamepad {
+        let eye = camera_transform.translation;
+        let aim = Quat::from_axis_angle(Vec3::Y, camera.yaw)
+            * Quat::from_axis_angle(Vec3::X, camera.pitch)
+            * Vec3::NEG_Z;
+
+        if let Some((target_direction, angle)) = find_aim_assist_target(eye, aim, &gamepad_config, &zombie_query, &spatial_query) {
+            // Slow turning while the crosshair is over a target so it's easier to stay on it
+            if angle < gamepad_config.slowdown_angle {
+                look_speed *= gamepad_config.slowdown_factor;
+            }
+
+            // Gently pull toward the target, but only while the player is actively aiming or moving
+            if angle < gamepad_config.magnetism_angle && (look != Vec2::ZERO || is_moving) {
+                let target_yaw = (-target_direction.x).atan2(-target_direction.z);
+                let target_pitch = target_direction.y.clamp(-1.0, 1.0).asin();
+                let pull = (gamepad_config.magnetism_strength * dt).min(1.0);
+
+                let yaw_error = (target_yaw - camera.yaw + PI).rem_euclid(TAU) - PI;
+                camera.yaw += yaw_error * pull;
+                camera.pitch += (target_pitch - camera.pitch) * pull;
+            }
+        }
+    }
+
+    camera.yaw -= look.x * look_speed.x * dt;
+    camera.pitch += look.y * look_speed.y * camera_settings.pitch_sign() * dt;
+    camera.pitch = camera_settings.clamp_pitch(camera.pitch);
+}
+
+/// Eases the aim-down-sights blend that narrows the view and slows looking.
+fn update_aim(
+    action_state: Res<ActionState<PlayerAction>>,
+    mut camera_query: Query<&mut FirstPersonCamera, With<GameCamera>>,
+    time: Res<Time>,
+) {
+    if let Ok(mut camera) = camera_query.get_single_mut() {
+        let target = if action_state.pressed(&PlayerAction::Aim) { 1.0 } else { 0.0 };
+        let blend = 1.0 - (-12.0 * time.delta_seconds()).exp();
+        camera.aim_blend += (target - camera.aim_blend) * blend;
+    }
+}
+
+/// Visible zombie closest to the crosshair, as a direction from the eye and its angle off the aim,
+/// measured to the edge of the zombie's collider.
+fn find_aim_assist_target(
+    eye: Vec3,
+    aim: Vec3,
+    gamepad_config: &GamepadConfig,
+    zombie_query: &Query<(&Transform, &Collider), (With<Zombie>, Without<GameCamera>)>,
+    spatial_query: &SpatialQuery,
+) -> Option<(Vec3, f32)> {
+    let max_angle = gamepad_config.slowdown_angle.max(gamepad_config.magnetism_angle);
+    let environment = SpatialQueryFilter::from_mask(CollisionLayer::Environment.mask());
+    let mut best: Option<(Vec3, f32)> = None;
+
+    for (zombie_transform, collider) in zombie_query.iter() {
+        let offset = zombie_transform.translation - eye;
+        let distance = offset.length();
+        if distance < 0.01 || distance > gamepad_config.aim_assist_range {
+            continue;
+        }
+
+        let direction = offset / distance;
+        let angular_radius = (collider.bounding_radius() / distance).atan();
+        let angle = (aim.angle_between(direction) - angular_radius).max(0.0);
+        if angle > max_angle || best.is_some_and(|(_, best_angle)| angle >= best_angle) {
+            continue;
+        }
+
+        // No help through walls
+        let occluded = spatial_query
+            .cast_ray(eye, direction, distance, &environment)
+            .is_some();
+        if !occluded {
+            best = Some((direction, angle));
+        }
+    }
+
+    best
+}
+
 fn handle_cursor_grab_ingame(
     mut windows: Query<&mut Window, With<PrimaryWindow>>,
-    mouse: Res<ButtonInput<MouseButton>>,
+    action_state: Res<ActionState<PlayerAction>>,
 ) {
     if let Ok(mut window) = windows.get_single_mut() {
-        if mouse.just_pressed(MouseButton::Left) {
+        if action_state.just_pressed(&PlayerAction::Shoot) {
             window.cursor.grab_mode = CursorGrabMode::Locked;
             window.cursor.visible = false;
         }
@@ -123,4 +499,175 @@ fn update_camera_effects(
         camera.distortion_strength = intensity * (camera.time_accumulator * 2.0).sin() * 0.1;
         camera.chromatic_aberration = intensity * (camera.time_accumulator * 3.0).cos() * 0.02;
     }
-} 
\ No newline at end of file
+}
+
+fn cycle_camera_mode(
+    action_state: Res<ActionState<PlayerAction>>,
+    mut camera_mode: ResMut<CameraMode>,
+) {
+    if action_state.just_pressed(&PlayerAction::CycleCamera) {
+        *camera_mode = camera_mode.next();
+    }
+}
+
+/// Pulls the camera back from the eye position `sync_camera_to_player` left it at,
+/// stopping short of anything in the way.
+pub fn position_camera_rig(
+    camera_mode: Res<CameraMode>,
+    rig: Res<CameraRigSettings>,
+    mut queries: ParamSet<(
+        Query<(&mut Transform, &mut FirstPersonCamera), With<GameCamera>>,
+        SpatialQuery,
+    )>,
+    time: Res<Time>,
+) {
+    let local_offset = match *camera_mode {
+        CameraMode::ThirdPerson => rig.shoulder_offset + Vec3::Z * rig.boom_length,
+        CameraMode::Spectator => Vec3::Z * rig.spectator_distance,
+        _ => {
+            if let Ok((transform, mut camera)) = queries.p0().get_single_mut() {
+                camera.boom_length = 0.0;
+                camera.eye = *transform;
+            }
+            return;
+        }
+    };
+
+    let Ok((eye, rotation, current_length)) = queries
+        .p0()
+        .get_single()
+        .map(|(transform, camera)| (transform.translation, transform.rotation, camera.boom_length))
+    else {
+        return;
+    };
+
+    let offset = rotation * local_offset;
+    let Some(direction) = offset.try_normalize() else {
+        return;
+    };
+    let max_length = offset.length();
+    let environment = SpatialQueryFilter::from_mask(CollisionLayer::Environment.mask());
+    let clear_length = queries
+        .p1()
+        .cast_sphere(eye, rig.collision_radius, direction, max_length, &environment)
+        .map_or(max_length, |hit| hit.distance);
+
+    // Snap in so walls never block the view, ease back out so the camera doesn't pop
+    let length = if clear_length < current_length {
+        clear_length
+    } else {
+        let blend = 1.0 - (-rig.boom_return_speed * time.delta_seconds()).exp();
+        current_length + (clear_length - current_length) * blend
+    };
+
+    if let Ok((mut transform, mut camera)) = queries.p0().get_single_mut() {
+        camera.boom_length = length;
+        camera.eye = Transform::from_translation(eye).with_rotation(rotation);
+        transform.translation = eye + direction * length;
+    }
+}
+
+fn update_player_model_visibility(
+    camera_mode: Res<CameraMode>,
+    mut model_query: Query<&mut Visibility, With<PlayerModel>>,
+) {
+    let visibility = if *camera_mode == CameraMode::FirstPerson {
+        Visibility::Hidden
+    } else {
+        Visibility::Inherited
+    };
+    for mut model_visibility in model_query.iter_mut() {
+        *model_visibility = visibility;
+    }
+}
+
+fn enter_spectator_camera(mut camera_mode: ResMut<CameraMode>) {
+    *camera_mode = CameraMode::Spectator;
+}
+
+fn leave_spectator_camera(mut camera_mode: ResMut<CameraMode>) {
+    *camera_mode = CameraMode::FirstPerson;
+}
+
+/// Spectator view circling where the player fell, behind the game over screen.
+fn orbit_game_over_camera(
+    rig: Res<CameraRigSettings>,
+    mut camera_query: Query<(&mut Transform, &mut FirstPersonCamera), With<GameCamera>>,
+    time: Res<Time>,
+) {
+    if let Ok((mut transform, mut camera)) = camera_query.get_single_mut() {
+        camera.yaw += rig.game_over_orbit_speed * time.delta_seconds();
+        let offset = Vec3::new(
+            camera.yaw.sin() * rig.game_over_orbit_distance,
+            rig.game_over_orbit_height,
+            camera.yaw.cos() * rig.game_over_orbit_distance,
+        );
+        // The player is gone by now; the last eye position marks where they fell
+        let focus = camera.eye.translation;
+        transform.translation = focus + offset;
+        transform.look_at(focus, Vec3::Y);
+    }
+}
+
+#[cfg(feature = "dev")]
+fn toggle_free_fly(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut camera_mode: ResMut<CameraMode>,
+) {
+    if keyboard_input.just_pressed(FREE_FLY_TOGGLE_KEY) {
+        *camera_mode = if *camera_mode == CameraMode::FreeFly {
+            CameraMode::FirstPerson
+        } else {
+            CameraMode::FreeFly
+        };
+    }
+}
+
+#[cfg(feature = "dev")]
+fn free_fly_camera(
+    camera_mode: Res<CameraMode>,
+    action_state: Res<ActionState<PlayerAction>>,
+    mut camera_query: Query<&mut Transform, With<GameCamera>>,
+    time: Res<Time>,
+) {
+    if *camera_mode != CameraMode::FreeFly {
+        return;
+    }
+    let Ok(mut transform) = camera_query.get_single_mut() else {
+        return;
+    };
+
+    let direction = fly_direction(&action_state, *transform.forward(), *transform.right());
+    let mut speed = FREE_FLY_SPEED;
+    if action_state.pressed(&PlayerAction::Sprint) {
+        speed *= FREE_FLY_SPRINT_MULTIPLIER;
+    }
+    transform.translation += direction * speed * time.delta_seconds();
+}
+
+/// Movement for a detached camera: the move actions along `forward`/`right`, jump and crouch for up and down.
+/// Clamped to unit length so diagonals aren't faster.
+pub fn fly_direction(action_state: &ActionState<PlayerAction>, forward: Vec3, right: Vec3) -> Vec3 {
+    let mut direction = Vec3::ZERO;
+    if action_state.pressed(&PlayerAction::MoveForward) {
+        direction += forward;
+    }
+    if action_state.pressed(&PlayerAction::MoveBackward) {
+        direction -= forward;
+    }
+    if action_state.pressed(&PlayerAction::MoveLeft) {
+        direction -= right;
+    }
+    if action_state.pressed(&PlayerAction::MoveRight) {
+        direction += right;
+    }
+    if action_state.pressed(&PlayerAction::Jump) {
+        direction += Vec3::Y;
+    }
+    if action_state.pressed(&PlayerAction::Crouch) {
+        direction -= Vec3::Y;
+    }
+    let stick = action_state.axis_pair(&PlayerAction::Move);
+    direction += forward * stick.y + right * stick.x;
+    direction.clamp_length_max(1.0)
+}
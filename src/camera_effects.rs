@@ -0,0 +1,258 @@
+use bevy::prelude::*;
+use leafwing_input_manager::prelude::*;
+use noise::{NoiseFn, Perlin};
+use crate::{
+    GameCamera, GameState, InRun, Player, PlayerHitEvent,
+    camera::{camera_follows_player, position_camera_rig, CameraSettings, FirstPersonCamera},
+    controls::PlayerAction,
+    physics::*,
+    player::{handle_shooting, MovementState},
+};
+
+pub struct CameraEffectsPlugin;
+
+impl Plugin for CameraEffectsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CameraEffectsSettings>()
+            .init_resource::<CameraEffectsState>()
+            .insert_resource(CameraNoise(Perlin::new(rand::random())))
+            .add_event::<CameraTraumaEvent>()
+            .add_systems(
+                Update,
+                (
+                    add_camera_trauma.after(handle_shooting),
+                    update_camera_motion,
+                    apply_camera_effects.after(position_camera_rig),
+                ).chain().run_if(in_state(GameState::InGame).and_then(camera_follows_player)),
+            )
+            .add_systems(OnExit(InRun), reset_camera_effects);
+    }
+}
+
+/// Something in the world worth shaking the camera for.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TraumaKind {
+    Kill,
+    Explosion,
+}
+
+/// Shakes the camera, less the further `position` is from it.
+#[derive(Event)]
+pub struct CameraTraumaEvent {
+    pub kind: TraumaKind,
+    pub position: Vec3,
+}
+
+#[derive(Resource)]
+pub struct CameraEffectsSettings {
+    /// Camera offset at full trauma, in metres.
+    pub max_shake_offset: f32,
+    /// Camera rotation at full trauma, in radians.
+    pub max_shake_angle: f32,
+    /// How fast the noise is scrolled; higher is more jittery.
+    pub shake_frequency: f32,
+    /// Trauma lost per second.
+    pub trauma_decay: f32,
+    pub shot_trauma: f32,
+    pub hit_trauma_per_damage: f32,
+    pub kill_trauma: f32,
+    pub explosion_trauma: f32,
+    /// World trauma fades to nothing at this distance from the camera.
+    pub trauma_falloff_distance: f32,
+    /// Extra field of view while sprinting or dashing, in degrees.
+    pub sprint_fov_kick: f32,
+    pub dash_fov_kick: f32,
+    pub fov_kick_speed: f32,
+    /// Dip per m/s of fall speed on landing.
+    pub landing_dip_per_speed: f32,
+    pub max_landing_dip: f32,
+    pub landing_recovery_speed: f32,
+    /// Bob cycles per metre walked.
+    pub bob_frequency: f32,
+    pub bob_amplitude: f32,
+}
+
+impl Default for CameraEffectsSettings {
+    fn default() -> Self {
+        Self {
+            max_shake_offset: 0.12,
+            max_shake_angle: 0.06,
+            shake_frequency: 18.0,
+            trauma_decay: 1.2,
+            shot_trauma: 0.12,
+            hit_trauma_per_damage: 0.02,
+            kill_trauma: 0.2,
+            explosion_trauma: 0.6,
+            trauma_falloff_distance: 15.0,
+            sprint_fov_kick: 6.0,
+            dash_fov_kick: 14.0,
+            fov_kick_speed: 8.0,
+            landing_dip_per_speed: 0.03,
+            max_landing_dip: 0.35,
+            landing_recovery_speed: 10.0,
+            bob_frequency: 0.35,
+            bob_amplitude: 0.05,
+        }
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct CameraEffectsState {
+    /// 0 to 1; the shake grows with its square so small knocks stay subtle.
+    pub trauma: f32,
+    noise_time: f32,
+    bob_phase: f32,
+    /// Eases the bob in and out as the player starts and stops walking.
+    bob_weight: f32,
+    fov_kick: f32,
+    landing_dip: f32,
+    /// Fastest downward speed since leaving the ground.
+    fall_speed: f32,
+    was_grounded: bool,
+}
+
+impl CameraEffectsState {
+    pub fn add_trauma(&mut self, amount: f32) {
+        self.trauma = (self.trauma + amount).min(1.0);
+    }
+}
+
+#[derive(Resource)]
+struct CameraNoise(Perlin);
+
+impl CameraNoise {
+    /// Smooth noise in -1..1 for one shake channel.
+    fn sample(&self, time: f32, channel: u32) -> f32 {
+        self.0.get([time as f64, channel as f64 * 17.3]) as f32
+    }
+}
+
+fn add_camera_trauma(
+    mut state: ResMut<CameraEffectsState>,
+    settings: Res<CameraEffectsSettings>,
+    mut trauma_events: EventReader<CameraTraumaEvent>,
+    mut hit_events: EventReader<PlayerHitEvent>,
+    player_query: Query<&Player>,
+    camera_query: Query<&Transform, With<GameCamera>>,
+) {
+    if player_query.get_single().is_ok_and(|player| player.shot_fired) {
+        state.add_trauma(settings.shot_trauma);
+    }
+
+    for event in hit_events.read() {
+        state.add_trauma((event.health_damage + event.armor_damage) * settings.hit_trauma_per_damage);
+    }
+
+    let camera_position = camera_query.get_single().map_or(Vec3::ZERO, |transform| transform.translation);
+    for event in trauma_events.read() {
+        let amount = match event.kind {
+            TraumaKind::Kill => settings.kill_trauma,
+            TraumaKind::Explosion => settings.explosion_trauma,
+        };
+        let falloff = 1.0 - event.position.distance(camera_position) / settings.trauma_falloff_distance;
+        if falloff > 0.0 {
+            state.add_trauma(amount * falloff);
+        }
+    }
+}
+
+/// Advances the trauma, FOV kick, landing dip and head bob from the player's movement.
+fn update_camera_motion(
+    mut state: ResMut<CameraEffectsState>,
+    settings: Res<CameraEffectsSettings>,
+    action_state: Res<ActionState<PlayerAction>>,
+    player_query: Query<(&Player, &RigidBody, &GroundDetector, &MovementState)>,
+    time: Res<Time>,
+) {
+    let dt = time.delta_seconds();
+    state.trauma = (state.trauma - settings.trauma_decay * dt).max(0.0);
+    state.noise_time += dt * settings.shake_frequency;
+
+    let Ok((player, rigidbody, ground_detector, movement)) = player_query.get_single() else {
+        return;
+    };
+    let horizontal_speed = Vec2::new(rigidbody.velocity.x, rigidbody.velocity.z).length();
+    let is_grounded = ground_detector.is_grounded;
+
+    // Widen the view while moving fast
+    let is_dashing = movement.dash_timer > 0.0;
+    let is_sprinting = action_state.pressed(&PlayerAction::Sprint) && !player.is_exhausted && horizontal_speed > 1.0;
+    let target_kick = if is_dashing {
+        settings.dash_fov_kick
+    } else if is_sprinting {
+        settings.sprint_fov_kick
+    } else {
+        0.0
+    };
+    let blend = 1.0 - (-settings.fov_kick_speed * dt).exp();
+    state.fov_kick += (target_kick - state.fov_kick) * blend;
+
+    // Dip on landing, harder the faster the fall
+    if is_grounded {
+        if !state.was_grounded {
+            state.landing_dip = (state.fall_speed * settings.landing_dip_per_speed).min(settings.max_landing_dip);
+        }
+        state.fall_speed = 0.0;
+    } else {
+        state.fall_speed = state.fall_speed.max(-rigidbody.velocity.y);
+    }
+    state.was_grounded = is_grounded;
+    state.landing_dip *= (-settings.landing_recovery_speed * dt).exp();
+
+    // Bob with distance walked so faster movement bobs faster
+    let is_walking = is_grounded && !movement.is_crouching && horizontal_speed > 0.5;
+    let target_weight = if is_walking { 1.0 } else { 0.0 };
+    state.bob_weight += (target_weight - state.bob_weight) * (1.0 - (-8.0 * dt).exp());
+    if is_walking {
+        state.bob_phase += horizontal_speed * settings.bob_frequency * std::f32::consts::TAU * dt;
+    }
+}
+
+/// Layers shake, bob, landing dip and FOV kick on top of the camera's base transform for this frame.
+fn apply_camera_effects(
+    state: Res<CameraEffectsState>,
+    settings: Res<CameraEffectsSettings>,
+    noise: Res<CameraNoise>,
+    camera_settings: Res<CameraSettings>,
+    mut camera_query: Query<(&mut Transform, &mut Projection, &FirstPersonCamera), With<GameCamera>>,
+) {
+    let Ok((mut transform, mut projection, camera)) = camera_query.get_single_mut() else {
+        return;
+    };
+    let scale = camera_settings.camera_motion;
+
+    let shake = state.trauma * state.trauma * scale;
+    let t = state.noise_time;
+    let shake_offset = Vec3::new(noise.sample(t, 0), noise.sample(t, 1), 0.0) * settings.max_shake_offset * shake;
+    let shake_rotation = Quat::from_euler(
+        EulerRot::YXZ,
+        noise.sample(t, 2) * settings.max_shake_angle * shake,
+        noise.sample(t, 3) * settings.max_shake_angle * shake,
+        noise.sample(t, 4) * settings.max_shake_angle * shake,
+    );
+
+    let bob = settings.bob_amplitude * state.bob_weight * scale;
+    let bob_offset = Vec3::new(state.bob_phase.sin() * bob * 0.5, state.bob_phase.sin().abs() * bob, 0.0);
+
+    let offset = transform.rotation * (shake_offset + bob_offset) - Vec3::Y * state.landing_dip * scale;
+    transform.translation += offset;
+    transform.rotation *= shake_rotation;
+
+    if let Projection::Perspective(perspective) = projection.as_mut() {
+        perspective.fov = (camera_settings.view_fov(camera.aim_blend) + state.fov_kick * scale).to_radians();
+    }
+}
+
+fn reset_camera_effects(
+    mut state: ResMut<CameraEffectsState>,
+    camera_settings: Res<CameraSettings>,
+    mut camera_query: Query<(&mut Projection, &mut FirstPersonCamera), With<GameCamera>>,
+) {
+    *state = CameraEffectsState::default();
+    if let Ok((mut projection, mut camera)) = camera_query.get_single_mut() {
+        camera.aim_blend = 0.0;
+        if let Projection::Perspective(perspective) = projection.as_mut() {
+            perspective.fov = camera_settings.fov_degrees.to_radians();
+        }
+    }
+}
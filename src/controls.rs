@@ -0,0 +1,307 @@
+use bevy::prelude::*;
+use leafwing_input_manager::prelude::*;
+use serde::{Deserialize, Serialize};
+use crate::persistence::{load_ron, save_ron};
+
+/// Where rebound controls are saved between sessions.
+const CONTROLS_PATH: &str = "controls.ron";
+
+pub struct ControlsPlugin;
+
+impl Plugin for ControlsPlugin {
+    fn build(&self, app: &mut App) {
+        let bindings = load_ron::<ControlBindings>(CONTROLS_PATH)
+            .map(ControlBindings::with_missing_defaults)
+            .unwrap_or_default();
+
+        app.add_plugins(InputManagerPlugin::<PlayerAction>::default())
+            .init_resource::<ActionState<PlayerAction>>()
+            .insert_resource(bindings.input_map())
+            .insert_resource(bindings)
+            .init_resource::<RebindState>()
+            .add_systems(Update, apply_control_bindings.run_if(resource_changed::<ControlBindings>));
+    }
+}
+
+#[derive(Actionlike, PartialEq, Eq, Hash, Clone, Copy, Debug, Reflect, Serialize, Deserialize)]
+pub enum PlayerAction {
+    MoveForward,
+    MoveBackward,
+    MoveLeft,
+    MoveRight,
+    Jump,
+    Sprint,
+    Crouch,
+    Dash,
+    Shoot,
+    /// Aim down sights: narrows the view and slows looking.
+    Aim,
+    Melee,
+    CycleCamera,
+    Pause,
+    /// Tilts the photo mode camera.
+    RollLeft,
+    RollRight,
+    /// Analog movement from the left stick, on top of the movement keys.
+    #[actionlike(DualAxis)]
+    Move,
+    #[actionlike(DualAxis)]
+    Look,
+}
+
+impl PlayerAction {
+    /// Button actions, in the order shown on the controls page. Stick axes aren't rebindable.
+    pub const REBINDABLE: [PlayerAction; 15] = [
+        PlayerAction::MoveForward,
+        PlayerAction::MoveBackward,
+        PlayerAction::MoveLeft,
+        PlayerAction::MoveRight,
+        PlayerAction::Jump,
+        PlayerAction::Sprint,
+        PlayerAction::Crouch,
+        PlayerAction::Dash,
+        PlayerAction::Shoot,
+        PlayerAction::Aim,
+        PlayerAction::Melee,
+        PlayerAction::CycleCamera,
+        PlayerAction::Pause,
+        PlayerAction::RollLeft,
+        PlayerAction::RollRight,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            PlayerAction::MoveForward => "Move Forward",
+            PlayerAction::MoveBackward => "Move Backward",
+            PlayerAction::MoveLeft => "Move Left",
+            PlayerAction::MoveRight => "Move Right",
+            PlayerAction::Jump => "Jump",
+            PlayerAction::Sprint => "Sprint",
+            PlayerAction::Crouch => "Crouch / Slide",
+            PlayerAction::Dash => "Dash",
+            PlayerAction::Shoot => "Shoot",
+            PlayerAction::Aim => "Aim",
+            PlayerAction::Melee => "Melee",
+            PlayerAction::CycleCamera => "Cycle Camera",
+            PlayerAction::Pause => "Pause",
+            PlayerAction::RollLeft => "Photo Roll Left",
+            PlayerAction::RollRight => "Photo Roll Right",
+            PlayerAction::Move => "Move",
+            PlayerAction::Look => "Look",
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum InputBinding {
+    Key(KeyCode),
+    Mouse(MouseButton),
+}
+
+impl InputBinding {
+    /// Short name shown on the controls page, e.g. "W" rather than "KeyW".
+    pub fn display_name(&self) -> String {
+        match self {
+            InputBinding::Key(key_code) => {
+                let name = format!("{:?}", key_code);
+                name.strip_prefix("Key")
+                    .or_else(|| name.strip_prefix("Digit"))
+                    .unwrap_or(&name)
+                    .to_string()
+            }
+            InputBinding::Mouse(mouse_button) => format!("Mouse {:?}", mouse_button),
+        }
+    }
+}
+
+/// One keyboard/mouse and one gamepad binding per action; the source of truth that the `InputMap` is rebuilt from.
+#[derive(Resource, Clone, Serialize, Deserialize)]
+pub struct ControlBindings {
+    pub bindings: Vec<(PlayerAction, InputBinding)>,
+    #[serde(default = "default_gamepad_bindings")]
+    pub gamepad_bindings: Vec<(PlayerAction, GamepadButtonType)>,
+}
+
+fn default_gamepad_bindings() -> Vec<(PlayerAction, GamepadButtonType)> {
+    vec![
+        (PlayerAction::Jump, GamepadButtonType::South),
+        (PlayerAction::Sprint, GamepadButtonType::LeftThumb),
+        (PlayerAction::Crouch, GamepadButtonType::East),
+        (PlayerAction::Dash, GamepadButtonType::LeftTrigger),
+        (PlayerAction::Shoot, GamepadButtonType::RightTrigger2),
+        (PlayerAction::Aim, GamepadButtonType::LeftTrigger2),
+        (PlayerAction::Melee, GamepadButtonType::RightTrigger),
+        (PlayerAction::CycleCamera, GamepadButtonType::North),
+        (PlayerAction::Pause, GamepadButtonType::Start),
+    ]
+}
+
+/// Binds `action` to `input` in `bindings`, swapping with whichever action previously used it.
+/// If `action` had no binding to swap, the other action is left unbound instead.
+fn rebind_in<T: PartialEq + Copy>(bindings: &mut Vec<(PlayerAction, T)>, action: PlayerAction, input: T) {
+    let previous = bindings
+        .iter()
+        .find(|(bound_action, _)| *bound_action == action)
+        .map(|(_, bound_input)| *bound_input);
+
+    match previous {
+        Some(previous) => {
+            for (bound_action, bound_input) in bindings.iter_mut() {
+                if *bound_action != action && *bound_input == input {
+                    *bound_input = previous;
+                }
+            }
+        }
+        None => bindings.retain(|(bound_action, bound_input)| *bound_action == action || *bound_input != input),
+    }
+
+    match bindings.iter_mut().find(|(bound_action, _)| *bound_action == action) {
+        Some((_, bound_input)) => *bound_input = input,
+        None => bindings.push((action, input)),
+    }
+}
+
+impl Default for ControlBindings {
+    fn default() -> Self {
+        Self {
+            bindings: vec![
+                (PlayerAction::MoveForward, InputBinding::Key(KeyCode::KeyW)),
+                (PlayerAction::MoveBackward, InputBinding::Key(KeyCode::KeyS)),
+                (PlayerAction::MoveLeft, InputBinding::Key(KeyCode::KeyA)),
+                (PlayerAction::MoveRight, InputBinding::Key(KeyCode::KeyD)),
+                (PlayerAction::Jump, InputBinding::Key(KeyCode::Space)),
+                (PlayerAction::Sprint, InputBinding::Key(KeyCode::ShiftLeft)),
+                (PlayerAction::Crouch, InputBinding::Key(KeyCode::KeyC)),
+                (PlayerAction::Dash, InputBinding::Key(KeyCode::KeyQ)),
+                (PlayerAction::Shoot, InputBinding::Mouse(MouseButton::Left)),
+                (PlayerAction::Aim, InputBinding::Mouse(MouseButton::Right)),
+                (PlayerAction::Melee, InputBinding::Key(KeyCode::KeyV)),
+                (PlayerAction::CycleCamera, InputBinding::Key(KeyCode::KeyT)),
+                (PlayerAction::Pause, InputBinding::Key(KeyCode::Escape)),
+                (PlayerAction::RollLeft, InputBinding::Key(KeyCode::KeyZ)),
+                (PlayerAction::RollRight, InputBinding::Key(KeyCode::KeyX)),
+            ],
+            gamepad_bindings: default_gamepad_bindings(),
+        }
+    }
+}
+
+impl ControlBindings {
+    pub fn binding(&self, action: PlayerAction) -> Option<InputBinding> {
+        self.bindings
+            .iter()
+            .find(|(bound_action, _)| *bound_action == action)
+            .map(|(_, binding)| *binding)
+    }
+
+    /// Fills in actions added since the bindings were saved, unless their default input is now used by another action.
+    pub fn with_missing_defaults(mut self) -> Self {
+        let defaults = ControlBindings::default();
+        for (action, binding) in defaults.bindings {
+            if self.binding(action).is_none() && !self.bindings.iter().any(|(_, bound)| *bound == binding) {
+                self.bindings.push((action, binding));
+            }
+        }
+        for (action, button_type) in defaults.gamepad_bindings {
+            if self.gamepad_binding(action).is_none() && !self.gamepad_bindings.iter().any(|(_, bound)| *bound == button_type) {
+                self.gamepad_bindings.push((action, button_type));
+            }
+        }
+        self
+    }
+
+    pub fn gamepad_binding(&self, action: PlayerAction) -> Option<GamepadButtonType> {
+        self.gamepad_bindings
+            .iter()
+            .find(|(bound_action, _)| *bound_action == action)
+            .map(|(_, button_type)| *button_type)
+    }
+
+    pub fn rebind(&mut self, action: PlayerAction, binding: InputBinding) {
+        rebind_in(&mut self.bindings, action, binding);
+    }
+
+    pub fn rebind_gamepad(&mut self, action: PlayerAction, button_type: GamepadButtonType) {
+        rebind_in(&mut self.gamepad_bindings, action, button_type);
+    }
+
+    /// Text for the controls page, e.g. "Space / South".
+    pub fn display_name(&self, action: PlayerAction) -> String {
+        let keyboard = self.binding(action).map_or("Unbound".to_string(), |binding| binding.display_name());
+        match self.gamepad_binding(action) {
+            Some(button_type) => format!("{} / {:?}", keyboard, button_type),
+            None => keyboard,
+        }
+    }
+
+    pub fn input_map(&self) -> InputMap<PlayerAction> {
+        let mut input_map = InputMap::default();
+        for (action, binding) in self.bindings.iter() {
+            match *binding {
+                InputBinding::Key(key_code) => input_map.insert(*action, key_code),
+                InputBinding::Mouse(mouse_button) => input_map.insert(*action, mouse_button),
+            };
+        }
+        for (action, button_type) in self.gamepad_bindings.iter() {
+            input_map.insert(*action, *button_type);
+        }
+        input_map.insert_dual_axis(PlayerAction::Move, GamepadStick::LEFT);
+        input_map.insert_dual_axis(PlayerAction::Look, GamepadStick::RIGHT);
+        input_map
+    }
+
+    pub fn save(&self) {
+        save_ron(CONTROLS_PATH, self);
+    }
+}
+
+/// The action waiting for its next key, mouse or gamepad press on the controls page.
+#[derive(Resource, Default)]
+pub struct RebindState {
+    pub waiting_for: Option<PlayerAction>,
+}
+
+fn apply_control_bindings(
+    bindings: Res<ControlBindings>,
+    mut input_map: ResMut<InputMap<PlayerAction>>,
+) {
+    *input_map = bindings.input_map();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rebind_swaps_with_the_action_that_had_the_input() {
+        let mut bindings = vec![(PlayerAction::Jump, KeyCode::Space), (PlayerAction::Sprint, KeyCode::ShiftLeft)];
+        rebind_in(&mut bindings, PlayerAction::Jump, KeyCode::ShiftLeft);
+        assert_eq!(bindings, vec![(PlayerAction::Jump, KeyCode::ShiftLeft), (PlayerAction::Sprint, KeyCode::Space)]);
+    }
+
+    #[test]
+    fn rebind_unbinds_the_other_action_when_there_is_nothing_to_swap() {
+        let mut bindings = vec![(PlayerAction::Sprint, KeyCode::ShiftLeft)];
+        rebind_in(&mut bindings, PlayerAction::Jump, KeyCode::ShiftLeft);
+        assert_eq!(bindings, vec![(PlayerAction::Jump, KeyCode::ShiftLeft)]);
+    }
+    #[test]
+    fn missing_defaults_fill_in_new_actions() {
+        let mut saved = ControlBindings::default();
+        saved.bindings.retain(|(action, _)| *action != PlayerAction::Crouch);
+
+        let bindings = saved.with_missing_defaults();
+        assert_eq!(bindings.binding(PlayerAction::Crouch), Some(InputBinding::Key(KeyCode::KeyC)));
+    }
+
+    #[test]
+    fn missing_defaults_skip_inputs_already_in_use() {
+        let mut saved = ControlBindings::default();
+        saved.bindings.retain(|(action, _)| *action != PlayerAction::Crouch);
+        saved.rebind(PlayerAction::Sprint, InputBinding::Key(KeyCode::KeyC));
+
+        let bindings = saved.with_missing_defaults();
+        assert_eq!(bindings.binding(PlayerAction::Crouch), None);
+        assert_eq!(bindings.binding(PlayerAction::Sprint), Some(InputBinding::Key(KeyCode::KeyC)));
+    }
+}
@@ -0,0 +1,237 @@
+use bevy::prelude::*;
+use rand::Rng;
+use crate::{GameState, InRun, physics::*, zombies::{Zombie, ZombieType}};
+
+pub struct DebrisPlugin;
+
+impl Plugin for DebrisPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<DebrisSettings>()
+            .add_systems(FixedUpdate, tumble_debris.run_if(in_state(GameState::InGame)))
+            .add_systems(
+                Update,
+                (
+                    enforce_debris_cap,
+                    update_debris,
+                ).chain().run_if(in_state(GameState::InGame)),
+            )
+            .add_systems(OnExit(InRun), cleanup_all_debris);
+    }
+}
+
+#[derive(Resource)]
+pub struct DebrisSettings {
+    /// Most chunks alive at once; the oldest are removed first when exceeded.
+    pub max_chunks: usize,
+    pub lifetime: f32,
+    /// Lifetime used when the budget is nearly full, so busy waves clear faster.
+    pub min_lifetime: f32,
+    pub fade_duration: f32,
+    /// Fraction of the killing hit's impulse passed on to each chunk.
+    pub impulse_transfer: f32,
+    pub scatter_speed: f32,
+    pub restitution: f32,
+}
+
+impl Default for DebrisSettings {
+    fn default() -> Self {
+        Self {
+            max_chunks: 160,
+            lifetime: 5.0,
+            min_lifetime: 1.5,
+            fade_duration: 1.0,
+            impulse_transfer: 0.3,
+            scatter_speed: 3.0,
+            restitution: 0.45,
+        }
+    }
+}
+
+impl DebrisSettings {
+    /// Shrinks the lifetime of new debris as the global budget fills up.
+    pub fn budgeted_lifetime(&self, active_chunks: usize) -> f32 {
+        let free = 1.0 - (active_chunks as f32 / self.max_chunks.max(1) as f32).min(1.0);
+        self.min_lifetime + (self.lifetime - self.min_lifetime) * free
+    }
+}
+
+#[derive(Component)]
+pub struct Debris {
+    pub lifetime: f32,
+    pub max_lifetime: f32,
+    pub fade_duration: f32,
+    pub angular_velocity: Vec3,
+}
+
+/// The dead zombie a burst of debris is cut from.
+pub struct DebrisSource<'a> {
+    pub transform: &'a Transform,
+    pub zombie: &'a Zombie,
+    pub body: &'a RigidBody,
+    /// Colours the chunks; white when the zombie's material isn't loaded.
+    pub material: Option<&'a StandardMaterial>,
+}
+
+/// Breaks a dead zombie's body into tumbling physics chunks, returning how many were spawned.
+pub fn spawn_zombie_debris(
+    commands: &mut Commands,
+    meshes: &mut ResMut<Assets<Mesh>>,
+    materials: &mut ResMut<Assets<StandardMaterial>>,
+    settings: &DebrisSettings,
+    source: DebrisSource,
+    lifetime: f32,
+) -> usize {
+    let mut rng = rand::thread_rng();
+    let DebrisSource { transform, zombie, body, material: source_material } = source;
+
+    // Body dimensions matching the zombie meshes, and how many chunks to cut them into
+    let (body_size, grid, is_round) = match zombie.zombie_type {
+        ZombieType::Basic => (Vec3::new(1.0, 2.0, 1.0), UVec3::new(2, 3, 2), false),
+        ZombieType::Fast => (Vec3::splat(1.6), UVec3::new(2, 2, 2), true),
+        ZombieType::Heavy => (Vec3::new(1.5, 2.5, 1.5), UVec3::new(2, 3, 2), false),
+        ZombieType::Exploder => (Vec3::splat(1.2), UVec3::new(2, 2, 2), true),
+    };
+    let chunk_size = body_size / grid.as_vec3();
+    let chunk_count = grid.x * grid.y * grid.z;
+    let chunk_mass = body.mass / chunk_count as f32;
+    let chunk_radius = chunk_size.min_element() * 0.5;
+
+    // Chunks of round bodies sit further in so the pieces still read as the original sphere
+    let (mesh, offset_scale) = if is_round {
+        (meshes.add(Mesh::from(Sphere::new(chunk_radius))), 0.7)
+    } else {
+        (meshes.add(Mesh::from(Cuboid::new(chunk_size.x, chunk_size.y, chunk_size.z))), 1.0)
+    };
+
+    let (base_color, emissive) = source_material
+        .map_or((Color::WHITE, LinearRgba::BLACK), |material| (material.base_color, material.emissive));
+
+    for x in 0..grid.x {
+        for y in 0..grid.y {
+            for z in 0..grid.z {
+                let cell = UVec3::new(x, y, z).as_vec3();
+                let local_offset = ((cell + Vec3::splat(0.5)) * chunk_size - body_size * 0.5) * offset_scale;
+                let offset = transform.rotation * local_offset;
+
+                // Inherit the body's motion, take a share of the killing blow and burst outward
+                let scatter = offset.normalize_or_zero() * settings.scatter_speed * rng.gen_range(0.5..1.5);
+                let velocity = body.velocity
+                    + zombie.last_hit_impulse * settings.impulse_transfer / chunk_mass
+                    + scatter
+                    + Vec3::Y * rng.gen_range(1.0..3.0);
+
+                // Each chunk fades on its own, so it needs its own material
+                let material = materials.add(StandardMaterial {
+                    base_color,
+                    emissive,
+                    alpha_mode: AlphaMode::Blend,
+                    ..default()
+                });
+
+                commands.spawn((
+                    PbrBundle {
+                        mesh: mesh.clone(),
+                        material,
+                        transform: Transform::from_translation(transform.translation + offset)
+                            .with_rotation(transform.rotation),
+                        ..default()
+                    },
+                    Debris {
+                        lifetime: 0.0,
+                        max_lifetime: lifetime * rng.gen_range(0.8..1.0),
+                        fade_duration: settings.fade_duration,
+                        angular_velocity: Vec3::new(
+                            rng.gen_range(-8.0..8.0),
+                            rng.gen_range(-8.0..8.0),
+                            rng.gen_range(-8.0..8.0),
+                        ),
+                    },
+                    RigidBody {
+                        velocity,
+                        mass: chunk_mass,
+                        friction: 2.0,
+                        restitution: settings.restitution,
+                        drag: 0.99,
+                        ..default()
+                    },
+                    Collider {
+                        shape: ColliderShape::Sphere { radius: chunk_radius },
+                        collision_layer: CollisionLayer::Debris,
+                        collision_mask: CollisionLayer::Environment.mask(),
+                    },
+                    GroundDetector {
+                        step_height: 0.0,
+                        ..default()
+                    },
+                ));
+            }
+        }
+    }
+
+    chunk_count as usize
+}
+
+fn tumble_debris(
+    mut debris_query: Query<(&mut Transform, &mut Debris, &GroundDetector)>,
+    time: Res<Time>,
+) {
+    let dt = time.delta_seconds();
+
+    for (mut transform, mut debris, ground_detector) in debris_query.iter_mut() {
+        // Spin dies down while rolling along the ground
+        if ground_detector.is_grounded {
+            debris.angular_velocity *= (-4.0 * dt).exp();
+        }
+        let spin = debris.angular_velocity * dt;
+        transform.rotate(Quat::from_scaled_axis(spin));
+    }
+}
+
+fn update_debris(
+    mut commands: Commands,
+    mut debris_query: Query<(Entity, &mut Debris, &Handle<StandardMaterial>)>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    time: Res<Time>,
+) {
+    for (entity, mut debris, material_handle) in debris_query.iter_mut() {
+        debris.lifetime += time.delta_seconds();
+
+        if debris.lifetime >= debris.max_lifetime {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        // Fade out over the last part of the lifetime
+        let remaining = debris.max_lifetime - debris.lifetime;
+        if remaining < debris.fade_duration {
+            if let Some(material) = materials.get_mut(material_handle) {
+                let alpha = remaining / debris.fade_duration;
+                material.base_color.set_alpha(alpha);
+            }
+        }
+    }
+}
+
+fn enforce_debris_cap(
+    mut debris_query: Query<&mut Debris>,
+    settings: Res<DebrisSettings>,
+) {
+    let active_chunks = debris_query.iter().len();
+    if active_chunks <= settings.max_chunks {
+        return;
+    }
+
+    // Expire the chunks closest to the end of their lifetime first
+    let mut chunks: Vec<Mut<Debris>> = debris_query.iter_mut().collect();
+    chunks.sort_by(|a, b| (a.max_lifetime - a.lifetime).total_cmp(&(b.max_lifetime - b.lifetime)));
+
+    for mut debris in chunks.into_iter().take(active_chunks - settings.max_chunks) {
+        debris.lifetime = debris.max_lifetime;
+    }
+}
+
+fn cleanup_all_debris(mut commands: Commands, debris_query: Query<Entity, With<Debris>>) {
+    for entity in debris_query.iter() {
+        commands.entity(entity).despawn();
+    }
+}
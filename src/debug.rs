@@ -0,0 +1,195 @@
+use bevy::prelude::*;
+use std::collections::VecDeque;
+use crate::physics::*;
+
+/// How long contact points stay on screen, in seconds.
+const CONTACT_DISPLAY_TIME: f32 = 1.0;
+const MAX_RECENT_CONTACTS: usize = 512;
+
+pub struct PhysicsDebugPlugin;
+
+impl Plugin for PhysicsDebugPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PhysicsDebugSettings>()
+            .init_resource::<RecentContacts>()
+            .add_systems(Update, (
+                toggle_physics_debug,
+                record_recent_contacts,
+                (
+                    draw_colliders,
+                    draw_velocities,
+                    draw_ground_probes,
+                    draw_spatial_hash,
+                    draw_recent_contacts,
+                ).run_if(physics_debug_enabled),
+            ).chain());
+    }
+}
+
+#[derive(Resource)]
+pub struct PhysicsDebugSettings {
+    pub enabled: bool,
+    pub toggle_key: KeyCode,
+    /// Seconds of travel drawn for each velocity arrow.
+    pub velocity_scale: f32,
+}
+
+impl Default for PhysicsDebugSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            toggle_key: KeyCode::F3,
+            velocity_scale: 0.25,
+        }
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct RecentContacts {
+    pub contacts: VecDeque<(Vec3, Vec3, f32)>, // Point, normal, time recorded
+}
+
+fn physics_debug_enabled(settings: Res<PhysicsDebugSettings>) -> bool {
+    settings.enabled
+}
+
+fn toggle_physics_debug(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut settings: ResMut<PhysicsDebugSettings>,
+) {
+    if keyboard_input.just_pressed(settings.toggle_key) {
+        settings.enabled = !settings.enabled;
+    }
+}
+
+fn layer_color(layer: CollisionLayer) -> Color {
+    match layer {
+        CollisionLayer::Default => Color::srgb(0.8, 0.8, 0.8),
+        CollisionLayer::Player => Color::srgb(0.0, 1.0, 0.0),
+        CollisionLayer::Zombie => Color::srgb(1.0, 0.2, 0.2),
+        CollisionLayer::Bullet => Color::srgb(1.0, 1.0, 0.0),
+        CollisionLayer::Environment => Color::srgb(0.3, 0.5, 1.0),
+        CollisionLayer::Debris => Color::srgb(0.6, 0.4, 0.2),
+    }
+}
+
+fn record_recent_contacts(
+    mut started_events: EventReader<CollisionStarted>,
+    mut ongoing_events: EventReader<CollisionOngoing>,
+    mut recent_contacts: ResMut<RecentContacts>,
+    time: Res<Time>,
+) {
+    let now = time.elapsed_seconds();
+
+    for (point, normal) in started_events.read().map(|event| (event.point, event.normal))
+        .chain(ongoing_events.read().map(|event| (event.point, event.normal)))
+    {
+        recent_contacts.contacts.push_back((point, normal, now));
+    }
+
+    while recent_contacts.contacts.len() > MAX_RECENT_CONTACTS
+        || recent_contacts.contacts.front().is_some_and(|&(_, _, recorded)| now - recorded > CONTACT_DISPLAY_TIME)
+    {
+        recent_contacts.contacts.pop_front();
+    }
+}
+
+fn draw_colliders(
+    mut gizmos: Gizmos,
+    collider_query: Query<(&Transform, &Collider, Has<Sensor>)>,
+) {
+    for (transform, collider, is_sensor) in collider_query.iter() {
+        let color = layer_color(collider.collision_layer);
+        // Sensors are drawn faded since nothing is pushed out of them
+        let color = if is_sensor { color.with_alpha(0.4) } else { color };
+        let position = transform.translation;
+
+        match collider.shape {
+            ColliderShape::Sphere { radius } => {
+                gizmos.sphere(position, transform.rotation, radius, color);
+            }
+            ColliderShape::Capsule { radius, half_height } => {
+                gizmos.primitive_3d(&Capsule3d::new(radius, half_height * 2.0), position, transform.rotation, color);
+            }
+            ColliderShape::Aabb { half_extents } => {
+                gizmos.cuboid(Transform::from_translation(position).with_scale(half_extents * 2.0), color);
+            }
+            ColliderShape::Obb { half_extents } => {
+                gizmos.cuboid(
+                    Transform::from_translation(position)
+                        .with_rotation(transform.rotation)
+                        .with_scale(half_extents * 2.0),
+                    color,
+                );
+            }
+        }
+    }
+}
+
+fn draw_velocities(
+    mut gizmos: Gizmos,
+    body_query: Query<(&Transform, &RigidBody)>,
+    settings: Res<PhysicsDebugSettings>,
+) {
+    for (transform, rigidbody) in body_query.iter() {
+        if rigidbody.velocity.length_squared() < 0.01 {
+            continue;
+        }
+        let end = transform.translation + rigidbody.velocity * settings.velocity_scale;
+        gizmos.arrow(transform.translation, end, Color::srgb(1.0, 0.5, 0.0));
+    }
+}
+
+fn draw_ground_probes(
+    mut gizmos: Gizmos,
+    detector_query: Query<(&Transform, &Collider, &GroundDetector)>,
+) {
+    for (transform, collider, ground_detector) in detector_query.iter() {
+        let feet = transform.translation - Vec3::Y * collider.half_extents(transform.rotation).y;
+        let probe_end = feet - Vec3::Y * ground_detector.probe_distance;
+        let color = if ground_detector.is_grounded {
+            Color::srgb(0.0, 1.0, 0.5)
+        } else {
+            Color::srgb(1.0, 0.0, 0.5)
+        };
+        gizmos.line(feet, probe_end, color);
+
+        if ground_detector.is_grounded {
+            let ground_point = feet - Vec3::Y * ground_detector.ground_distance;
+            gizmos.arrow(ground_point, ground_point + ground_detector.ground_normal * 0.5, color);
+        }
+    }
+}
+
+fn draw_spatial_hash(
+    mut gizmos: Gizmos,
+    spatial_hash: Res<SpatialHash>,
+) {
+    let cell_size = spatial_hash.cell_size;
+
+    for (cell, entities) in spatial_hash.cells.iter() {
+        // Cells holding several colliders are where pairs actually get tested
+        let color = if entities.len() > 1 {
+            Color::srgba(1.0, 1.0, 1.0, 0.25)
+        } else {
+            Color::srgba(1.0, 1.0, 1.0, 0.05)
+        };
+        let center = (cell.as_vec3() + Vec3::splat(0.5)) * cell_size;
+        gizmos.cuboid(Transform::from_translation(center).with_scale(Vec3::splat(cell_size)), color);
+    }
+}
+
+fn draw_recent_contacts(
+    mut gizmos: Gizmos,
+    recent_contacts: Res<RecentContacts>,
+    time: Res<Time>,
+) {
+    let now = time.elapsed_seconds();
+
+    for &(point, normal, recorded) in recent_contacts.contacts.iter() {
+        let fade = 1.0 - ((now - recorded) / CONTACT_DISPLAY_TIME).clamp(0.0, 1.0);
+        let color = Color::srgba(1.0, 1.0, 0.0, fade);
+        gizmos.sphere(point, Quat::IDENTITY, 0.05, color);
+        gizmos.line(point, point + normal * 0.3, color);
+    }
+}
@@ -1,20 +1,22 @@
 use bevy::prelude::*;
-use crate::GameConfig;
+use crate::{GameConfig, controls::{ControlBindings, PlayerAction}};
 
 pub struct GameStatePlugin;
 
 impl Plugin for GameStatePlugin {
     fn build(&self, app: &mut App) {
         app.init_state::<GameState>()
+            .add_computed_state::<InRun>()
             .init_resource::<GameConfig>()
             .init_resource::<GameStats>()
+            .init_resource::<RunResults>()
             .init_resource::<SteamConfig>()
             .add_systems(Startup, setup_game_state)
             .add_systems(Update, (
                 update_game_stats,
                 handle_game_over_condition,
-                update_high_score,
-            ));
+            ))
+            .add_systems(OnExit(InRun), record_run_results);
     }
 }
 
@@ -27,6 +29,37 @@ pub enum GameState {
     Paused,
     GameOver,
     Settings,
+    Controls,
+    /// Field of view, sensitivity and other look options, reached from the settings page.
+    CameraSettings,
+    /// Between waves, choosing a perk.
+    PerkDraft,
+    /// Replaying the last few seconds after the player dies, before the game over screen.
+    KillCam,
+    Unlocks,
+    Codex,
+    /// Free camera over the frozen run, entered from the pause menu.
+    PhotoMode,
+}
+
+/// Active while a run is in progress, including while paused, drafting perks or in photo mode.
+/// Run entities are spawned on entering it and cleaned up on leaving it.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct InRun;
+
+impl ComputedStates for InRun {
+    type SourceStates = GameState;
+
+    fn compute(state: GameState) -> Option<Self> {
+        match state {
+            GameState::InGame
+            | GameState::Paused
+            | GameState::PerkDraft
+            | GameState::KillCam
+            | GameState::PhotoMode => Some(InRun),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Resource)]
@@ -50,6 +83,13 @@ impl Default for GameStats {
     }
 }
 
+/// Final numbers of the most recent run, captured before the player is cleaned up.
+#[derive(Resource, Default)]
+pub struct RunResults {
+    pub score: f32,
+    pub kills: u32,
+}
+
 #[derive(Resource)]
 pub struct SteamConfig {
     pub app_id: u32,
@@ -67,14 +107,13 @@ impl Default for SteamConfig {
     }
 }
 
-fn setup_game_state(_commands: Commands) {
+fn setup_game_state(_commands: Commands, bindings: Res<ControlBindings>) {
     println!("Game State System Initialized");
-    println!("Controls:");
-    println!("- WASD: Move");
+    println!("Controls (rebind in Settings > Controls):");
     println!("- Mouse: Look around");
-    println!("- Left Click: Grab cursor and shoot");
-    println!("- Escape: Pause/Menu");
-    println!("- Shift: Sprint");
+    for action in PlayerAction::REBINDABLE {
+        println!("- {}: {}", bindings.display_name(action), action.label());
+    }
 }
 
 fn update_game_stats(
@@ -95,23 +134,30 @@ fn handle_game_over_condition(
     if *state.get() == GameState::InGame {
         if let Ok(player) = player_query.get_single() {
             if player.health <= 0.0 {
-                next_state.set(GameState::GameOver);
+                next_state.set(GameState::KillCam);
             }
         }
     }
 }
 
-fn update_high_score(
+fn record_run_results(
     player_query: Query<&crate::Player>,
+    mut run_results: ResMut<RunResults>,
     mut game_stats: ResMut<GameStats>,
-    state: Res<State<GameState>>,
 ) {
-    if *state.get() == GameState::GameOver {
-        if let Ok(player) = player_query.get_single() {
-            if player.score > game_stats.high_score {
-                game_stats.high_score = player.score;
-            }
-        }
+    let Ok(player) = player_query.get_single() else {
+        return;
+    };
+
+    *run_results = RunResults {
+        score: player.score,
+        kills: player.kill_count,
+    };
+    game_stats.games_played += 1;
+    game_stats.total_kills += player.kill_count;
+    game_stats.total_score += player.score;
+    if player.score > game_stats.high_score {
+        game_stats.high_score = player.score;
     }
 }
 
@@ -0,0 +1,236 @@
+use bevy::prelude::*;
+use bevy::input::mouse::MouseMotion;
+use bevy::ui::UiSystem;
+use crate::{GameState, controls::RebindState, menu::{ButtonAction, MenuButton, MenuUI}};
+
+pub struct GamepadPlugin;
+
+impl Plugin for GamepadPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GamepadConfig>()
+            .init_resource::<InputDevice>()
+            .init_resource::<MenuFocus>()
+            .add_systems(PreUpdate, (
+                track_input_device.after(bevy::input::InputSystem),
+                // After UI focus so the menus see the press as a normal click this frame
+                gamepad_menu_navigation
+                    .after(UiSystem::Focus)
+                    .run_if(not(in_state(GameState::InGame))),
+            ));
+    }
+}
+
+#[derive(Resource)]
+pub struct GamepadConfig {
+    pub move_deadzone: f32,
+    pub look_deadzone: f32,
+    /// Exponent applied to stick deflection past the deadzone; above 1 gives finer control near the centre.
+    pub move_curve: f32,
+    pub look_curve: f32,
+    /// Turn rate in radians per second at full deflection (yaw, pitch).
+    pub look_speed: Vec2,
+    pub aim_assist: bool,
+    pub aim_assist_range: f32,
+    /// Half-angle of the cone around the crosshair in which a target slows turning.
+    pub slowdown_angle: f32,
+    pub slowdown_factor: f32,
+    /// Half-angle of the cone in which the crosshair is pulled toward a target.
+    pub magnetism_angle: f32,
+    /// Fraction of the angle to the target closed per second while the player is aiming or moving.
+    pub magnetism_strength: f32,
+}
+
+impl Default for GamepadConfig {
+    fn default() -> Self {
+        Self {
+            move_deadzone: 0.15,
+            look_deadzone: 0.12,
+            move_curve: 1.0,
+            look_curve: 2.0,
+            look_speed: Vec2::new(3.5, 2.5),
+            aim_assist: true,
+            aim_assist_range: 40.0,
+            slowdown_angle: 0.08,
+            slowdown_factor: 0.45,
+            magnetism_angle: 0.12,
+            magnetism_strength: 2.5,
+        }
+    }
+}
+
+/// Rescales a stick past a radial deadzone and applies a response curve to its magnitude.
+pub fn apply_stick_response(stick: Vec2, deadzone: f32, curve: f32) -> Vec2 {
+    let magnitude = stick.length().min(1.0);
+    if magnitude <= deadzone {
+        return Vec2::ZERO;
+    }
+
+    let scaled = ((magnitude - deadzone) / (1.0 - deadzone)).powf(curve);
+    stick.normalize() * scaled
+}
+
+/// Which device the player touched last; aim assist only helps controller players.
+#[derive(Resource, Default, PartialEq, Eq, Clone, Copy)]
+pub enum InputDevice {
+    #[default]
+    KeyboardMouse,
+    Gamepad,
+}
+
+fn track_input_device(
+    mut input_device: ResMut<InputDevice>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    mut mouse_motion: EventReader<MouseMotion>,
+    gamepad_buttons: Res<ButtonInput<GamepadButton>>,
+    gamepad_axes: Res<Axis<GamepadAxis>>,
+    gamepads: Res<Gamepads>,
+    config: Res<GamepadConfig>,
+) {
+    let used_keyboard_mouse = keyboard_input.get_just_pressed().next().is_some()
+        || mouse_input.get_just_pressed().next().is_some()
+        || mouse_motion.read().any(|motion| motion.delta.length_squared() > 1.0);
+
+    let used_gamepad = gamepad_buttons.get_just_pressed().next().is_some()
+        || gamepads.iter().any(|gamepad| {
+            [GamepadAxisType::LeftStickX, GamepadAxisType::LeftStickY, GamepadAxisType::RightStickX, GamepadAxisType::RightStickY]
+                .into_iter()
+                .any(|axis_type| {
+                    gamepad_axes
+                        .get(GamepadAxis::new(gamepad, axis_type))
+                        .is_some_and(|value| value.abs() > config.look_deadzone)
+                })
+        });
+
+    let device = if used_gamepad {
+        InputDevice::Gamepad
+    } else if used_keyboard_mouse {
+        InputDevice::KeyboardMouse
+    } else {
+        return;
+    };
+    if *input_device != device {
+        *input_device = device;
+    }
+}
+
+/// Menu button selected with the D-pad, and the one pressed last frame that needs releasing.
+#[derive(Resource, Default)]
+pub struct MenuFocus {
+    pub focused: Option<Entity>,
+    pub pressed: Option<Entity>,
+}
+
+const FOCUSED_BORDER_COLOR: Color = Color::WHITE;
+const UNFOCUSED_BORDER_COLOR: Color = Color::srgb(1.0, 0.0, 1.0);
+
+fn gamepad_menu_navigation(
+    gamepads: Res<Gamepads>,
+    gamepad_buttons: Res<ButtonInput<GamepadButton>>,
+    mut menu_focus: ResMut<MenuFocus>,
+    rebind_state: Res<RebindState>,
+    mut button_query: Query<(Entity, &MenuButton, &GlobalTransform, &mut Interaction, &mut BorderColor), With<MenuUI>>,
+) {
+    // Release the button "clicked" last frame so it can be pressed again
+    if let Some(pressed) = menu_focus.pressed.take() {
+        if let Ok((_, _, _, mut interaction, _)) = button_query.get_mut(pressed) {
+            *interaction = Interaction::None;
+        }
+    }
+
+    // The next gamepad press becomes a binding on the controls page
+    if rebind_state.waiting_for.is_some() {
+        return;
+    }
+
+    let just_pressed = |button_type: GamepadButtonType| {
+        gamepads
+            .iter()
+            .any(|gamepad| gamepad_buttons.just_pressed(GamepadButton::new(gamepad, button_type)))
+    };
+    let step: i32 = if just_pressed(GamepadButtonType::DPadDown) || just_pressed(GamepadButtonType::DPadRight) {
+        1
+    } else if just_pressed(GamepadButtonType::DPadUp) || just_pressed(GamepadButtonType::DPadLeft) {
+        -1
+    } else {
+        0
+    };
+    let activate = just_pressed(GamepadButtonType::South);
+
+    // East backs out of the current menu
+    if just_pressed(GamepadButtonType::East) {
+        if let Some((entity, _, _, mut interaction, _)) = button_query
+            .iter_mut()
+            .find(|(_, menu_button, _, _, _)| matches!(menu_button.action, ButtonAction::Back | ButtonAction::ResumeGame))
+        {
+            *interaction = Interaction::Pressed;
+            menu_focus.pressed = Some(entity);
+        }
+        return;
+    }
+
+    if step == 0 && !activate {
+        return;
+    }
+
+    // Order buttons as they appear on screen: top to bottom, then left to right
+    let mut buttons: Vec<(Entity, Vec3)> = button_query
+        .iter()
+        .map(|(entity, _, transform, _, _)| (entity, transform.translation()))
+        .collect();
+    if buttons.is_empty() {
+        return;
+    }
+    buttons.sort_by(|a, b| a.1.y.total_cmp(&b.1.y).then(a.1.x.total_cmp(&b.1.x)));
+
+    let current = menu_focus
+        .focused
+        .and_then(|focused| buttons.iter().position(|(entity, _)| *entity == focused));
+    let next = match current {
+        Some(index) => (index as i32 + step).rem_euclid(buttons.len() as i32) as usize,
+        None => 0,
+    };
+    let focused = buttons[next].0;
+    menu_focus.focused = Some(focused);
+
+    for (entity, _, _, mut interaction, mut border_color) in button_query.iter_mut() {
+        border_color.0 = if entity == focused {
+            FOCUSED_BORDER_COLOR
+        } else {
+            UNFOCUSED_BORDER_COLOR
+        };
+
+        // The first press only selects a button; later presses activate it
+        if entity == focused && activate && current.is_some() {
+            *interaction = Interaction::Pressed;
+            menu_focus.pressed = Some(entity);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stick_response_ignores_input_inside_the_deadzone() {
+        assert_eq!(apply_stick_response(Vec2::new(0.1, 0.05), 0.15, 1.0), Vec2::ZERO);
+    }
+
+    #[test]
+    fn stick_response_rescales_past_the_deadzone() {
+        let deadzone = 0.2;
+        // Just past the deadzone starts from zero, full tilt still reaches one
+        assert!(apply_stick_response(Vec2::new(0.21, 0.0), deadzone, 1.0).length() < 0.02);
+        assert!((apply_stick_response(Vec2::new(0.0, 1.0), deadzone, 2.0) - Vec2::Y).length() < 1e-6);
+
+        let half = apply_stick_response(Vec2::new(0.6, 0.0), deadzone, 2.0);
+        assert!((half - Vec2::new(0.25, 0.0)).length() < 1e-6);
+    }
+
+    #[test]
+    fn stick_response_caps_magnitude_at_one() {
+        let diagonal = apply_stick_response(Vec2::new(1.0, 1.0), 0.1, 1.5);
+        assert!((diagonal.length() - 1.0).abs() < 1e-6);
+    }
+}
@@ -1,5 +1,10 @@
 use bevy::prelude::*;
-use crate::GameConfig;
+use crate::{GameConfig, physics::*};
+
+/// Distance from the arena center to the center line of each wall.
+pub const ARENA_HALF_SIZE: f32 = 25.0;
+pub const ARENA_WALL_HEIGHT: f32 = 10.0;
+pub const ARENA_WALL_THICKNESS: f32 = 1.0;
 
 pub struct GraphicsPlugin;
 
@@ -105,7 +110,8 @@ fn setup_environment(
     commands.init_resource::<GraphicsSettings>();
 
     // Create a psychedelic arena floor
-    let floor_mesh = meshes.add(Mesh::from(Plane3d::default().mesh().size(50.0, 50.0)));
+    let floor_size = ARENA_HALF_SIZE * 2.0;
+    let floor_mesh = meshes.add(Mesh::from(Plane3d::default().mesh().size(floor_size, floor_size)));
     let floor_material = materials.add(StandardMaterial {
         base_color: Color::srgb(0.1, 0.1, 0.2),
         emissive: Color::srgb(0.05, 0.0, 0.1).into(),
@@ -117,12 +123,23 @@ fn setup_environment(
     commands.spawn(PbrBundle {
         mesh: floor_mesh,
         material: floor_material,
-        transform: Transform::from_xyz(0.0, -1.0, 0.0),
+        transform: Transform::from_xyz(0.0, 0.0, 0.0),
         ..default()
     });
 
+    // Floor collider: a slab whose top face is the visible floor plane
+    commands.spawn((
+        TransformBundle::from(Transform::from_xyz(0.0, -0.5, 0.0)),
+        Collider::environment(ColliderShape::Aabb {
+            half_extents: Vec3::new(ARENA_HALF_SIZE, 0.5, ARENA_HALF_SIZE),
+        }),
+    ));
+
     // Create psychedelic arena walls
     create_arena_walls(&mut commands, &mut meshes, &mut materials);
+
+    // Ledges, jump pads and platforms to give the arena some height
+    create_vertical_features(&mut commands, &mut meshes, &mut materials);
 }
 
 fn create_arena_walls(
@@ -130,10 +147,10 @@ fn create_arena_walls(
     meshes: &mut ResMut<Assets<Mesh>>,
     materials: &mut ResMut<Assets<StandardMaterial>>,
 ) {
-    let wall_height = 10.0;
-    let arena_size = 25.0;
+    let wall_height = ARENA_WALL_HEIGHT;
+    let arena_size = ARENA_HALF_SIZE;
     
-    let wall_mesh = meshes.add(Mesh::from(Cuboid::new(1.0, wall_height, arena_size * 2.0)));
+    let wall_mesh = meshes.add(Mesh::from(Cuboid::new(ARENA_WALL_THICKNESS, wall_height, arena_size * 2.0)));
     let wall_material = materials.add(StandardMaterial {
         base_color: Color::srgb(0.2, 0.0, 0.3),
         emissive: Color::srgb(0.1, 0.0, 0.2).into(),
@@ -151,16 +168,114 @@ fn create_arena_walls(
     ];
 
     for (position, rotation) in wall_positions {
-        commands.spawn(PbrBundle {
-            mesh: wall_mesh.clone(),
-            material: wall_material.clone(),
-            transform: Transform::from_translation(position)
-                .with_rotation(Quat::from_euler(EulerRot::XYZ, rotation.x, rotation.y, rotation.z)),
-            ..default()
-        });
+        commands.spawn((
+            PbrBundle {
+                mesh: wall_mesh.clone(),
+                material: wall_material.clone(),
+                transform: Transform::from_translation(position)
+                    .with_rotation(Quat::from_euler(EulerRot::XYZ, rotation.x, rotation.y, rotation.z)),
+                ..default()
+            },
+            Collider::environment(ColliderShape::Obb {
+                half_extents: Vec3::new(ARENA_WALL_THICKNESS * 0.5, wall_height * 0.5, arena_size),
+            }),
+        ));
     }
 }
 
+fn create_vertical_features(
+    commands: &mut Commands,
+    meshes: &mut ResMut<Assets<Mesh>>,
+    materials: &mut ResMut<Assets<StandardMaterial>>,
+) {
+    let ledge_material = materials.add(StandardMaterial {
+        base_color: Color::srgb(0.15, 0.0, 0.25),
+        emissive: Color::srgb(0.1, 0.0, 0.3).into(),
+        metallic: 0.7,
+        perceptual_roughness: 0.3,
+        ..default()
+    });
+    let jump_pad_material = materials.add(StandardMaterial {
+        base_color: Color::srgb(0.0, 1.0, 0.8),
+        emissive: Color::srgb(0.0, 1.5, 1.0).into(),
+        ..default()
+    });
+    let platform_material = materials.add(StandardMaterial {
+        base_color: Color::srgb(1.0, 0.0, 0.6),
+        emissive: Color::srgb(0.6, 0.0, 0.4).into(),
+        metallic: 0.9,
+        perceptual_roughness: 0.1,
+        ..default()
+    });
+
+    // Raised ledges along the east and west walls, reachable with a single jump
+    let ledge_half_extents = Vec3::new(3.0, 1.5, 10.0);
+    let ledge_x = ARENA_HALF_SIZE - ARENA_WALL_THICKNESS * 0.5 - ledge_half_extents.x;
+    for x in [ledge_x, -ledge_x] {
+        commands.spawn((
+            PbrBundle {
+                mesh: meshes.add(Mesh::from(Cuboid::from_size(ledge_half_extents * 2.0))),
+                material: ledge_material.clone(),
+                transform: Transform::from_xyz(x, ledge_half_extents.y, 0.0),
+                ..default()
+            },
+            Collider::environment(ColliderShape::Aabb { half_extents: ledge_half_extents }),
+        ));
+    }
+
+    // Jump pads out in the open between the ledges, launching the player clear of the horde
+    let jump_pad_half_extents = Vec3::new(1.2, 0.1, 1.2);
+    for x in [8.0, -8.0] {
+        commands.spawn((
+            PbrBundle {
+                mesh: meshes.add(Mesh::from(Cuboid::from_size(jump_pad_half_extents * 2.0))),
+                material: jump_pad_material.clone(),
+                transform: Transform::from_xyz(x, jump_pad_half_extents.y, 0.0),
+                ..default()
+            },
+            Collider::environment(ColliderShape::Aabb { half_extents: jump_pad_half_extents }),
+            JumpPad::default(),
+        ));
+    }
+
+    // A platform sliding across the north side of the arena
+    let sliding_half_extents = Vec3::new(2.0, 0.25, 2.0);
+    let sliding_origin = Vec3::new(-8.0, 5.0, 18.0);
+    commands.spawn((
+        PbrBundle {
+            mesh: meshes.add(Mesh::from(Cuboid::from_size(sliding_half_extents * 2.0))),
+            material: platform_material.clone(),
+            transform: Transform::from_translation(sliding_origin),
+            ..default()
+        },
+        Collider::environment(ColliderShape::Obb { half_extents: sliding_half_extents }),
+        MovingPlatform {
+            origin: sliding_origin,
+            travel: Vec3::new(16.0, 0.0, 0.0),
+            period: 10.0,
+            ..default()
+        },
+    ));
+
+    // A slowly spinning bridge on the south side
+    let spinning_half_extents = Vec3::new(5.0, 0.25, 1.0);
+    let spinning_origin = Vec3::new(0.0, 3.0, -18.0);
+    commands.spawn((
+        PbrBundle {
+            mesh: meshes.add(Mesh::from(Cuboid::from_size(spinning_half_extents * 2.0))),
+            material: platform_material,
+            transform: Transform::from_translation(spinning_origin),
+            ..default()
+        },
+        Collider::environment(ColliderShape::Obb { half_extents: spinning_half_extents }),
+        MovingPlatform {
+            origin: spinning_origin,
+            angular_speed: 0.5,
+            ..default()
+        },
+    ));
+}
+
 fn update_lighting_effects(
     mut light_query: Query<(&mut PointLight, &mut PsychedelicLight)>,
     time: Res<Time>,
@@ -206,11 +321,3 @@ fn update_fog_effects(
     // Dynamic fog density
     graphics_settings.fog_density = 0.02 + (time_factor * 0.5).sin() * 0.01 * intensity;
 }
-
-// TODO: Implement custom shaders for advanced psychedelic effects
-// This would include:
-// - Fisheye distortion shader
-// - Chromatic aberration
-// - Rainbow light trails
-// - Kaleidoscope effects
-// - Screen-space distortions 
\ No newline at end of file
@@ -6,6 +6,7 @@ use bevy_inspector_egui::DefaultInspectorConfigPlugin;
 use iyes_perf_ui::prelude::*;
 
 mod camera;
+mod camera_effects;
 mod player;
 mod zombies;
 mod weapons;
@@ -16,8 +17,21 @@ mod physics;
 mod game_state;
 mod particles;
 mod menu;
+mod debris;
+mod controls;
+mod persistence;
+mod photo_mode;
+mod post_process;
+mod gamepad;
+mod powerups;
+mod perks;
+mod progression;
+mod replay;
+#[cfg(feature = "dev")]
+mod debug;
 
 use camera::*;
+use camera_effects::*;
 use player::*;
 use zombies::*;
 use weapons::*;
@@ -28,6 +42,17 @@ use physics::*;
 use game_state::*;
 use particles::*;
 use menu::*;
+use debris::*;
+use controls::*;
+use gamepad::*;
+use photo_mode::*;
+use post_process::*;
+use powerups::*;
+use perks::*;
+use progression::*;
+use replay::*;
+#[cfg(feature = "dev")]
+use debug::*;
 
 fn main() {
     let mut app = App::new();
@@ -43,7 +68,7 @@ fn main() {
         }))
         .add_plugins((
             // Core game plugins
-            CameraPlugin,
+            (CameraPlugin, CameraEffectsPlugin, PostProcessPlugin, PhotoModePlugin),
             PlayerPlugin,
             ZombiePlugin,
             WeaponsPlugin,
@@ -54,12 +79,18 @@ fn main() {
             GameStatePlugin,
             ParticlePlugin,
             MenuPlugin,
+            // Death presentation
+            (DebrisPlugin, ReplayPlugin),
+            // Run modifiers
+            (PowerUpPlugin, PerkPlugin, ProgressionPlugin),
+            // Input
+            (ControlsPlugin, GamepadPlugin),
             // Performance UI
             PerfUiPlugin,
         ));
 
     #[cfg(feature = "dev")]
-    app.add_plugins(DefaultInspectorConfigPlugin);
+    app.add_plugins((DefaultInspectorConfigPlugin, PhysicsDebugPlugin));
     
     app.run();
 }
@@ -69,7 +100,6 @@ pub struct GameCamera;
 
 #[derive(Resource)]
 pub struct GameConfig {
-    pub mouse_sensitivity: f32,
     pub movement_speed: f32,
     pub psychedelic_intensity: f32,
     pub zombie_spawn_rate: f32,
@@ -79,7 +109,6 @@ pub struct GameConfig {
 impl Default for GameConfig {
     fn default() -> Self {
         Self {
-            mouse_sensitivity: 0.002,
             movement_speed: 5.0,
             psychedelic_intensity: 1.0,
             zombie_spawn_rate: 1.0,
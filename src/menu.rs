@@ -1,6 +1,7 @@
 use bevy::prelude::*;
 use bevy::app::AppExit;
-use crate::{GameState, Player, GameStats, GameConfig};
+use leafwing_input_manager::prelude::*;
+use crate::{GameState, GameStats, GameConfig, RunResults, camera::{CameraSetting, CameraSettings}, controls::*, progression::*};
 
 pub struct MenuPlugin;
 
@@ -32,7 +33,7 @@ impl Plugin for MenuPlugin {
         )
         .add_systems(
             OnEnter(GameState::GameOver),
-            setup_game_over_menu,
+            setup_game_over_menu.after(award_run_currency),
         )
         .add_systems(
             Update,
@@ -54,6 +55,63 @@ impl Plugin for MenuPlugin {
             OnExit(GameState::Settings),
             cleanup_menu,
         )
+        .add_systems(
+            OnEnter(GameState::Controls),
+            setup_controls_menu,
+        )
+        .add_systems(
+            Update,
+            (
+                (controls_menu_system, capture_rebind_input, update_binding_labels).chain(),
+                update_menu_effects,
+            ).run_if(in_state(GameState::Controls)),
+        )
+        .add_systems(
+            OnExit(GameState::Controls),
+            (cleanup_menu, cancel_rebind),
+        )
+        .add_systems(
+            OnEnter(GameState::CameraSettings),
+            setup_camera_settings_menu,
+        )
+        .add_systems(
+            Update,
+            (
+                (camera_settings_menu_system, update_camera_setting_labels).chain(),
+                update_menu_effects,
+            ).run_if(in_state(GameState::CameraSettings)),
+        )
+        .add_systems(
+            OnExit(GameState::CameraSettings),
+            cleanup_menu,
+        )
+        .add_systems(
+            OnEnter(GameState::Unlocks),
+            setup_unlocks_menu,
+        )
+        .add_systems(
+            Update,
+            (
+                (unlocks_menu_system, update_unlock_labels).chain(),
+                update_menu_effects,
+            ).run_if(in_state(GameState::Unlocks)),
+        )
+        .add_systems(
+            OnExit(GameState::Unlocks),
+            cleanup_menu,
+        )
+        .add_systems(
+            OnEnter(GameState::Codex),
+            setup_codex_menu,
+        )
+        .add_systems(
+            Update,
+            (codex_menu_system, update_menu_effects).run_if(in_state(GameState::Codex)),
+        )
+        .add_systems(
+            OnExit(GameState::Codex),
+            cleanup_menu,
+        )
         .add_systems(
             Update,
             handle_pause_input.run_if(in_state(GameState::InGame)),
@@ -75,6 +133,27 @@ pub struct MenuTitle;
 #[derive(Component)]
 pub struct MenuBackground;
 
+/// Text showing the current binding of `action` on the controls page.
+#[derive(Component)]
+pub struct BindingLabel {
+    pub action: PlayerAction,
+}
+
+/// Text showing the current value of `setting` on the camera settings page.
+#[derive(Component)]
+pub struct CameraSettingLabel {
+    pub setting: CameraSetting,
+}
+
+/// Text on an unlock tree node, showing its price or whether it's owned.
+#[derive(Component)]
+pub struct UnlockLabel {
+    pub unlock: Unlock,
+}
+
+#[derive(Component)]
+pub struct CurrencyText;
+
 #[derive(Component)]
 pub struct PsychedelicMenuEffect {
     pub phase: f32,
@@ -91,8 +170,23 @@ pub enum ButtonAction {
     Quit,
     IncreaseVolume,
     DecreaseVolume,
-    IncreaseSensitivity,
-    DecreaseSensitivity,
+    IncreaseCameraSetting(CameraSetting),
+    DecreaseCameraSetting(CameraSetting),
+    ResetCameraSettings,
+    IncreaseCameraMotion,
+    DecreaseCameraMotion,
+    Controls,
+    CameraSettings,
+    Rebind(PlayerAction),
+    ResetControls,
+    Unlocks,
+    Codex,
+    /// Buys the unlock, or equips it if already owned.
+    BuyUnlock(Unlock),
+    /// Takes the perk card at this index in the current draft.
+    PickPerk(usize),
+    PhotoMode,
+    SaveScreenshot,
     Back,
 }
 
@@ -130,6 +224,8 @@ fn setup_main_menu(mut commands: Commands) {
         ));
 
         create_menu_button(parent, "START GAME", ButtonAction::StartGame);
+        create_menu_button(parent, "UNLOCKS", ButtonAction::Unlocks);
+        create_menu_button(parent, "CODEX", ButtonAction::Codex);
         create_menu_button(parent, "SETTINGS", ButtonAction::Settings);
         create_menu_button(parent, "QUIT", ButtonAction::Quit);
     });
@@ -173,6 +269,7 @@ fn setup_pause_menu(mut commands: Commands) {
         ));
 
         create_menu_button(parent, "RESUME", ButtonAction::ResumeGame);
+        create_menu_button(parent, "PHOTO MODE", ButtonAction::PhotoMode);
         create_menu_button(parent, "SETTINGS", ButtonAction::Settings);
         create_menu_button(parent, "MAIN MENU", ButtonAction::MainMenu);
     });
@@ -181,14 +278,12 @@ fn setup_pause_menu(mut commands: Commands) {
 // Game Over Menu
 fn setup_game_over_menu(
     mut commands: Commands,
-    player_query: Query<&Player>,
+    run_results: Res<RunResults>,
+    rewards: Res<RunRewards>,
+    profile: Res<Profile>,
     game_stats: Res<GameStats>,
 ) {
-    let final_score = if let Ok(player) = player_query.get_single() {
-        player.score
-    } else {
-        0.0
-    };
+    let final_score = run_results.score;
 
     commands.spawn((
         NodeBundle {
@@ -251,12 +346,27 @@ fn setup_game_over_menu(
                 },
             )
             .with_style(Style {
-                margin: UiRect::bottom(Val::Px(50.0)),
+                margin: UiRect::bottom(Val::Px(20.0)),
                 ..default()
             }),
             PsychedelicMenuEffect { phase: 3.14, speed: 1.5 },
         ));
 
+        parent.spawn(
+            TextBundle::from_section(
+                format!("+{} shards ({} total)", rewards.earned, profile.currency),
+                TextStyle {
+                    font_size: 28.0,
+                    color: Color::srgb(0.0, 1.0, 1.0),
+                    ..default()
+                },
+            )
+            .with_style(Style {
+                margin: UiRect::bottom(Val::Px(50.0)),
+                ..default()
+            }),
+        );
+
         create_menu_button(parent, "RESTART", ButtonAction::RestartGame);
         create_menu_button(parent, "MAIN MENU", ButtonAction::MainMenu);
         create_menu_button(parent, "QUIT", ButtonAction::Quit);
@@ -264,7 +374,7 @@ fn setup_game_over_menu(
 }
 
 // Settings Menu
-fn setup_settings_menu(mut commands: Commands, config: Res<GameConfig>) {
+fn setup_settings_menu(mut commands: Commands, config: Res<GameConfig>, camera_settings: Res<CameraSettings>) {
     commands.spawn((
         NodeBundle {
             style: Style {
@@ -300,12 +410,27 @@ fn setup_settings_menu(mut commands: Commands, config: Res<GameConfig>) {
             PsychedelicMenuEffect { phase: 0.0, speed: 2.5 },
         ));
 
-        create_setting_row(parent, "Mouse Sensitivity", config.mouse_sensitivity, 
-                          ButtonAction::DecreaseSensitivity, ButtonAction::IncreaseSensitivity);
-
-        create_setting_row(parent, "Movement Speed", config.movement_speed,
+        create_setting_row(parent, "Movement Speed", &format!("{:.2}", config.movement_speed), (),
                           ButtonAction::DecreaseVolume, ButtonAction::IncreaseVolume);
 
+        create_setting_row(parent, "Camera Motion", &format!("{:.2}", camera_settings.camera_motion), (),
+                          ButtonAction::DecreaseCameraMotion, ButtonAction::IncreaseCameraMotion);
+
+        parent.spawn((
+            NodeBundle {
+                style: Style {
+                    flex_direction: FlexDirection::Row,
+                    ..default()
+                },
+                ..default()
+            },
+            MenuUI,
+        ))
+        .with_children(|parent| {
+            create_menu_button(parent, "CONTROLS", ButtonAction::Controls);
+            create_menu_button(parent, "CAMERA", ButtonAction::CameraSettings);
+        });
+
         parent.spawn((
             NodeBundle {
                 style: Style {
@@ -322,53 +447,136 @@ fn setup_settings_menu(mut commands: Commands, config: Res<GameConfig>) {
     });
 }
 
-fn create_menu_button(parent: &mut ChildBuilder, text: &str, action: ButtonAction) {
-    parent.spawn((
-        ButtonBundle {
+// Camera Settings Menu
+fn setup_camera_settings_menu(mut commands: Commands, settings: Res<CameraSettings>) {
+    commands.spawn((
+        NodeBundle {
             style: Style {
-                width: Val::Px(300.0),
-                height: Val::Px(60.0),
-                margin: UiRect::all(Val::Px(10.0)),
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
                 justify_content: JustifyContent::Center,
                 align_items: AlignItems::Center,
-                border: UiRect::all(Val::Px(3.0)),
+                flex_direction: FlexDirection::Column,
                 ..default()
             },
-            background_color: Color::srgba(0.2, 0.0, 0.4, 0.8).into(),
-            border_color: Color::srgb(1.0, 0.0, 1.0).into(),
+            background_color: Color::srgba(0.0, 0.1, 0.2, 0.9).into(),
             ..default()
         },
-        MenuButton { action },
         MenuUI,
+        MenuBackground,
         PsychedelicMenuEffect { phase: 0.0, speed: 1.0 },
     ))
     .with_children(|parent| {
         parent.spawn((
             TextBundle::from_section(
-                text,
+                "CAMERA",
                 TextStyle {
-                    font_size: 24.0,
-                    color: Color::srgb(1.0, 1.0, 1.0),
+                    font_size: 60.0,
+                    color: Color::srgb(0.0, 1.0, 1.0),
                     ..default()
                 },
-            ),
+            )
+            .with_style(Style {
+                margin: UiRect::bottom(Val::Px(20.0)),
+                ..default()
+            }),
+            MenuTitle,
+            PsychedelicMenuEffect { phase: 0.0, speed: 2.5 },
+        ));
+
+        for setting in CameraSetting::ALL {
+            create_setting_row(parent, setting.label(), &setting.display_value(&settings), CameraSettingLabel { setting },
+                              ButtonAction::DecreaseCameraSetting(setting), ButtonAction::IncreaseCameraSetting(setting));
+        }
+
+        parent.spawn((
+            NodeBundle {
+                style: Style {
+                    margin: UiRect::top(Val::Px(20.0)),
+                    flex_direction: FlexDirection::Row,
+                    ..default()
+                },
+                ..default()
+            },
             MenuUI,
+        ))
+        .with_children(|parent| {
+            create_menu_button(parent, "RESET DEFAULTS", ButtonAction::ResetCameraSettings);
+            create_menu_button(parent, "BACK", ButtonAction::Back);
+        });
+    });
+}
+
+// Controls Menu
+fn setup_controls_menu(mut commands: Commands, bindings: Res<ControlBindings>) {
+    commands.spawn((
+        NodeBundle {
+            style: Style {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                flex_direction: FlexDirection::Column,
+                ..default()
+            },
+            background_color: Color::srgba(0.0, 0.1, 0.2, 0.9).into(),
+            ..default()
+        },
+        MenuUI,
+        MenuBackground,
+        PsychedelicMenuEffect { phase: 0.0, speed: 1.0 },
+    ))
+    .with_children(|parent| {
+        parent.spawn((
+            TextBundle::from_section(
+                "CONTROLS",
+                TextStyle {
+                    font_size: 60.0,
+                    color: Color::srgb(0.0, 1.0, 1.0),
+                    ..default()
+                },
+            )
+            .with_style(Style {
+                margin: UiRect::bottom(Val::Px(20.0)),
+                ..default()
+            }),
+            MenuTitle,
+            PsychedelicMenuEffect { phase: 0.0, speed: 2.5 },
         ));
+
+        for action in PlayerAction::REBINDABLE {
+            create_binding_row(parent, action, &bindings.display_name(action));
+        }
+
+        parent.spawn((
+            NodeBundle {
+                style: Style {
+                    margin: UiRect::top(Val::Px(20.0)),
+                    flex_direction: FlexDirection::Row,
+                    ..default()
+                },
+                ..default()
+            },
+            MenuUI,
+        ))
+        .with_children(|parent| {
+            create_menu_button(parent, "RESET DEFAULTS", ButtonAction::ResetControls);
+            create_menu_button(parent, "BACK", ButtonAction::Back);
+        });
     });
 }
 
-fn create_setting_row(parent: &mut ChildBuilder, label: &str, value: f32, 
-                     decrease_action: ButtonAction, increase_action: ButtonAction) {
+fn create_binding_row(parent: &mut ChildBuilder, action: PlayerAction, binding_text: &str) {
     parent.spawn((
         NodeBundle {
             style: Style {
-                width: Val::Percent(80.0),
-                height: Val::Px(80.0),
-                margin: UiRect::all(Val::Px(10.0)),
+                width: Val::Px(600.0),
+                height: Val::Px(50.0),
+                margin: UiRect::all(Val::Px(4.0)),
                 justify_content: JustifyContent::SpaceBetween,
                 align_items: AlignItems::Center,
                 flex_direction: FlexDirection::Row,
-                padding: UiRect::all(Val::Px(20.0)),
+                padding: UiRect::horizontal(Val::Px(20.0)),
                 border: UiRect::all(Val::Px(2.0)),
                 ..default()
             },
@@ -381,7 +589,7 @@ fn create_setting_row(parent: &mut ChildBuilder, label: &str, value: f32,
     .with_children(|parent| {
         parent.spawn((
             TextBundle::from_section(
-                label,
+                action.label(),
                 TextStyle {
                     font_size: 20.0,
                     color: Color::srgb(1.0, 1.0, 1.0),
@@ -392,75 +600,352 @@ fn create_setting_row(parent: &mut ChildBuilder, label: &str, value: f32,
         ));
 
         parent.spawn((
-            NodeBundle {
+            ButtonBundle {
                 style: Style {
+                    width: Val::Px(220.0),
+                    height: Val::Px(36.0),
                     justify_content: JustifyContent::Center,
                     align_items: AlignItems::Center,
-                    flex_direction: FlexDirection::Row,
+                    border: UiRect::all(Val::Px(2.0)),
                     ..default()
                 },
+                background_color: Color::srgba(0.3, 0.0, 0.6, 0.8).into(),
+                border_color: Color::srgb(1.0, 0.0, 1.0).into(),
                 ..default()
             },
+            MenuButton { action: ButtonAction::Rebind(action) },
             MenuUI,
         ))
         .with_children(|parent| {
-            create_small_button(parent, "-", decrease_action.clone());
-
             parent.spawn((
                 TextBundle::from_section(
-                    format!("{:.2}", value),
+                    binding_text,
                     TextStyle {
                         font_size: 18.0,
                         color: Color::srgb(0.0, 1.0, 0.0),
                         ..default()
                     },
                 ),
+                BindingLabel { action },
                 MenuUI,
             ));
-
-            create_small_button(parent, "+", increase_action);
         });
     });
 }
 
-fn create_small_button(parent: &mut ChildBuilder, text: &str, action: ButtonAction) {
-    parent.spawn((
-        ButtonBundle {
+// Unlocks Menu
+fn setup_unlocks_menu(mut commands: Commands, profile: Res<Profile>) {
+    commands.spawn((
+        NodeBundle {
             style: Style {
-                width: Val::Px(40.0),
-                height: Val::Px(40.0),
-                margin: UiRect::horizontal(Val::Px(10.0)),
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
                 justify_content: JustifyContent::Center,
                 align_items: AlignItems::Center,
-                border: UiRect::all(Val::Px(2.0)),
+                flex_direction: FlexDirection::Column,
                 ..default()
             },
-            background_color: Color::srgba(0.3, 0.0, 0.6, 0.8).into(),
-            border_color: Color::srgb(1.0, 0.0, 1.0).into(),
+            background_color: Color::srgba(0.1, 0.0, 0.2, 0.9).into(),
             ..default()
         },
-        MenuButton { action },
         MenuUI,
+        MenuBackground,
+        PsychedelicMenuEffect { phase: 0.0, speed: 1.0 },
     ))
     .with_children(|parent| {
         parent.spawn((
             TextBundle::from_section(
-                text,
+                "UNLOCKS",
                 TextStyle {
-                    font_size: 18.0,
-                    color: Color::srgb(1.0, 1.0, 1.0),
+                    font_size: 60.0,
+                    color: Color::srgb(1.0, 0.0, 1.0),
                     ..default()
                 },
-            ),
+            )
+            .with_style(Style {
+                margin: UiRect::bottom(Val::Px(10.0)),
+                ..default()
+            }),
+            MenuTitle,
+            PsychedelicMenuEffect { phase: 0.0, speed: 2.5 },
+        ));
+
+        parent.spawn((
+            TextBundle::from_section(
+                format!("{} shards", profile.currency),
+                TextStyle {
+                    font_size: 28.0,
+                    color: Color::srgb(0.0, 1.0, 1.0),
+                    ..default()
+                },
+            )
+            .with_style(Style {
+                margin: UiRect::bottom(Val::Px(20.0)),
+                ..default()
+            }),
+            CurrencyText,
             MenuUI,
         ));
+
+        // One column per category, each a chain of nodes from cheapest to most advanced
+        parent.spawn((
+            NodeBundle {
+                style: Style {
+                    flex_direction: FlexDirection::Row,
+                    column_gap: Val::Px(20.0),
+                    ..default()
+                },
+                ..default()
+            },
+            MenuUI,
+        ))
+        .with_children(|parent| {
+            for category in UnlockCategory::ALL {
+                parent.spawn((
+                    NodeBundle {
+                        style: Style {
+                            flex_direction: FlexDirection::Column,
+                            align_items: AlignItems::Center,
+                            ..default()
+                        },
+                        ..default()
+                    },
+                    MenuUI,
+                ))
+                .with_children(|parent| {
+                    parent.spawn((
+                        TextBundle::from_section(
+                            category.label(),
+                            TextStyle {
+                                font_size: 22.0,
+                                color: Color::srgb(1.0, 1.0, 0.0),
+                                ..default()
+                            },
+                        )
+                        .with_style(Style {
+                            margin: UiRect::bottom(Val::Px(10.0)),
+                            ..default()
+                        }),
+                        MenuUI,
+                    ));
+
+                    for definition in UNLOCKS.iter().filter(|definition| definition.category == category) {
+                        create_unlock_node(parent, definition.unlock, &unlock_label(&profile, definition.unlock));
+                    }
+                });
+            }
+        });
+
+        parent.spawn((
+            NodeBundle {
+                style: Style {
+                    margin: UiRect::top(Val::Px(30.0)),
+                    ..default()
+                },
+                ..default()
+            },
+            MenuUI,
+        ))
+        .with_children(|parent| {
+            create_menu_button(parent, "BACK", ButtonAction::Back);
+        });
     });
 }
 
-// Menu Systems
-fn main_menu_system(
-    mut next_state: ResMut<NextState<GameState>>,
-    mut interaction_query: Query<
+fn create_unlock_node(parent: &mut ChildBuilder, unlock: Unlock, label: &str) {
+    parent.spawn((
+        ButtonBundle {
+            style: Style {
+                width: Val::Px(240.0),
+                height: Val::Px(50.0),
+                margin: UiRect::all(Val::Px(5.0)),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                border: UiRect::all(Val::Px(2.0)),
+                ..default()
+            },
+            background_color: Color::srgba(0.3, 0.0, 0.6, 0.8).into(),
+            border_color: Color::srgb(1.0, 0.0, 1.0).into(),
+            ..default()
+        },
+        MenuButton { action: ButtonAction::BuyUnlock(unlock) },
+        MenuUI,
+    ))
+    .with_children(|parent| {
+        parent.spawn((
+            TextBundle::from_section(
+                label,
+                TextStyle {
+                    font_size: 16.0,
+                    color: Color::srgb(1.0, 1.0, 1.0),
+                    ..default()
+                },
+            ),
+            UnlockLabel { unlock },
+            MenuUI,
+        ));
+    });
+}
+
+/// e.g. "Scattergun - 60 shards", "Scattergun - EQUIPPED" or "Rail Rifle - needs Scattergun".
+fn unlock_label(profile: &Profile, unlock: Unlock) -> String {
+    let definition = unlock.definition();
+    let status = if profile.is_equipped(unlock) {
+        "EQUIPPED".to_string()
+    } else if profile.has(unlock) && (unlock.weapon().is_some() || unlock.theme().is_some()) {
+        "EQUIP".to_string()
+    } else if profile.has(unlock) {
+        "OWNED".to_string()
+    } else if let Some(required) = definition.requires.filter(|required| !profile.has(*required)) {
+        format!("needs {}", required.definition().name)
+    } else {
+        format!("{} shards", definition.cost)
+    };
+
+    format!("{} - {}", definition.name, status)
+}
+
+fn create_menu_button(parent: &mut ChildBuilder, text: &str, action: ButtonAction) {
+    parent.spawn((
+        ButtonBundle {
+            style: Style {
+                width: Val::Px(300.0),
+                height: Val::Px(60.0),
+                margin: UiRect::all(Val::Px(10.0)),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                border: UiRect::all(Val::Px(3.0)),
+                ..default()
+            },
+            background_color: Color::srgba(0.2, 0.0, 0.4, 0.8).into(),
+            border_color: Color::srgb(1.0, 0.0, 1.0).into(),
+            ..default()
+        },
+        MenuButton { action },
+        MenuUI,
+        PsychedelicMenuEffect { phase: 0.0, speed: 1.0 },
+    ))
+    .with_children(|parent| {
+        parent.spawn((
+            TextBundle::from_section(
+                text,
+                TextStyle {
+                    font_size: 24.0,
+                    color: Color::srgb(1.0, 1.0, 1.0),
+                    ..default()
+                },
+            ),
+            MenuUI,
+        ));
+    });
+}
+
+/// A labelled value with -/+ buttons; `value_marker` tags the value text so it can be kept up to date.
+fn create_setting_row(parent: &mut ChildBuilder, label: &str, value_text: &str, value_marker: impl Bundle,
+                     decrease_action: ButtonAction, increase_action: ButtonAction) {
+    parent.spawn((
+        NodeBundle {
+            style: Style {
+                width: Val::Percent(80.0),
+                height: Val::Px(80.0),
+                margin: UiRect::all(Val::Px(10.0)),
+                justify_content: JustifyContent::SpaceBetween,
+                align_items: AlignItems::Center,
+                flex_direction: FlexDirection::Row,
+                padding: UiRect::all(Val::Px(20.0)),
+                border: UiRect::all(Val::Px(2.0)),
+                ..default()
+            },
+            background_color: Color::srgba(0.1, 0.1, 0.3, 0.6).into(),
+            border_color: Color::srgb(0.5, 0.5, 1.0).into(),
+            ..default()
+        },
+        MenuUI,
+    ))
+    .with_children(|parent| {
+        parent.spawn((
+            TextBundle::from_section(
+                label,
+                TextStyle {
+                    font_size: 20.0,
+                    color: Color::srgb(1.0, 1.0, 1.0),
+                    ..default()
+                },
+            ),
+            MenuUI,
+        ));
+
+        parent.spawn((
+            NodeBundle {
+                style: Style {
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    flex_direction: FlexDirection::Row,
+                    ..default()
+                },
+                ..default()
+            },
+            MenuUI,
+        ))
+        .with_children(|parent| {
+            create_small_button(parent, "-", decrease_action.clone());
+
+            parent.spawn((
+                TextBundle::from_section(
+                    value_text,
+                    TextStyle {
+                        font_size: 18.0,
+                        color: Color::srgb(0.0, 1.0, 0.0),
+                        ..default()
+                    },
+                ),
+                MenuUI,
+                value_marker,
+            ));
+
+            create_small_button(parent, "+", increase_action);
+        });
+    });
+}
+
+fn create_small_button(parent: &mut ChildBuilder, text: &str, action: ButtonAction) {
+    parent.spawn((
+        ButtonBundle {
+            style: Style {
+                width: Val::Px(40.0),
+                height: Val::Px(40.0),
+                margin: UiRect::horizontal(Val::Px(10.0)),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                border: UiRect::all(Val::Px(2.0)),
+                ..default()
+            },
+            background_color: Color::srgba(0.3, 0.0, 0.6, 0.8).into(),
+            border_color: Color::srgb(1.0, 0.0, 1.0).into(),
+            ..default()
+        },
+        MenuButton { action },
+        MenuUI,
+    ))
+    .with_children(|parent| {
+        parent.spawn((
+            TextBundle::from_section(
+                text,
+                TextStyle {
+                    font_size: 18.0,
+                    color: Color::srgb(1.0, 1.0, 1.0),
+                    ..default()
+                },
+            ),
+            MenuUI,
+        ));
+    });
+}
+
+// Menu Systems
+fn main_menu_system(
+    mut next_state: ResMut<NextState<GameState>>,
+    mut interaction_query: Query<
         (&Interaction, &MenuButton),
         (Changed<Interaction>, With<Button>),
     >,
@@ -470,6 +955,8 @@ fn main_menu_system(
         if *interaction == Interaction::Pressed {
             match menu_button.action {
                 ButtonAction::StartGame => next_state.set(GameState::InGame),
+                ButtonAction::Unlocks => next_state.set(GameState::Unlocks),
+                ButtonAction::Codex => next_state.set(GameState::Codex),
                 ButtonAction::Settings => next_state.set(GameState::Settings),
                 ButtonAction::Quit => { exit.send(AppExit::Success); },
                 _ => {}
@@ -489,6 +976,7 @@ fn pause_menu_system(
         if *interaction == Interaction::Pressed {
             match menu_button.action {
                 ButtonAction::ResumeGame => next_state.set(GameState::InGame),
+                ButtonAction::PhotoMode => next_state.set(GameState::PhotoMode),
                 ButtonAction::Settings => next_state.set(GameState::Settings),
                 ButtonAction::MainMenu => next_state.set(GameState::MainMenu),
                 _ => {}
@@ -524,22 +1012,27 @@ fn settings_menu_system(
         (Changed<Interaction>, With<Button>),
     >,
     mut config: ResMut<GameConfig>,
+    mut camera_settings: ResMut<CameraSettings>,
 ) {
     for (interaction, menu_button) in &mut interaction_query {
         if *interaction == Interaction::Pressed {
             match menu_button.action {
-                ButtonAction::IncreaseSensitivity => {
-                    config.mouse_sensitivity = (config.mouse_sensitivity + 0.001).min(0.01);
-                }
-                ButtonAction::DecreaseSensitivity => {
-                    config.mouse_sensitivity = (config.mouse_sensitivity - 0.001).max(0.0001);
-                }
                 ButtonAction::IncreaseVolume => {
                     config.movement_speed = (config.movement_speed + 0.5).min(15.0);
                 }
                 ButtonAction::DecreaseVolume => {
                     config.movement_speed = (config.movement_speed - 0.5).max(1.0);
                 }
+                ButtonAction::IncreaseCameraMotion => {
+                    camera_settings.camera_motion = (camera_settings.camera_motion + 0.25).min(2.0);
+                    camera_settings.save();
+                }
+                ButtonAction::DecreaseCameraMotion => {
+                    camera_settings.camera_motion = (camera_settings.camera_motion - 0.25).max(0.0);
+                    camera_settings.save();
+                }
+                ButtonAction::Controls => next_state.set(GameState::Controls),
+                ButtonAction::CameraSettings => next_state.set(GameState::CameraSettings),
                 ButtonAction::Back => next_state.set(GameState::MainMenu),
                 _ => {}
             }
@@ -547,20 +1040,344 @@ fn settings_menu_system(
     }
 }
 
-fn handle_pause_input(
-    keyboard_input: Res<ButtonInput<KeyCode>>,
+fn camera_settings_menu_system(
     mut next_state: ResMut<NextState<GameState>>,
+    mut interaction_query: Query<
+        (&Interaction, &MenuButton),
+        (Changed<Interaction>, With<Button>),
+    >,
+    mut settings: ResMut<CameraSettings>,
+) {
+    for (interaction, menu_button) in &mut interaction_query {
+        if *interaction == Interaction::Pressed {
+            match menu_button.action {
+                ButtonAction::IncreaseCameraSetting(setting) => {
+                    setting.adjust(&mut settings, 1.0);
+                    settings.save();
+                }
+                ButtonAction::DecreaseCameraSetting(setting) => {
+                    setting.adjust(&mut settings, -1.0);
+                    settings.save();
+                }
+                ButtonAction::ResetCameraSettings => {
+                    // Camera motion lives on the main settings page, so this page leaves it alone
+                    *settings = CameraSettings {
+                        camera_motion: settings.camera_motion,
+                        ..default()
+                    };
+                    settings.save();
+                }
+                ButtonAction::Back => next_state.set(GameState::Settings),
+                _ => {}
+            }
+        }
+    }
+}
+
+fn update_camera_setting_labels(
+    settings: Res<CameraSettings>,
+    mut label_query: Query<(&CameraSettingLabel, &mut Text)>,
+) {
+    if !settings.is_changed() {
+        return;
+    }
+
+    for (label, mut text) in label_query.iter_mut() {
+        if let Some(section) = text.sections.get_mut(0) {
+            section.value = label.setting.display_value(&settings);
+        }
+    }
+}
+
+fn controls_menu_system(
+    mut next_state: ResMut<NextState<GameState>>,
+    mut interaction_query: Query<
+        (&Interaction, &MenuButton),
+        (Changed<Interaction>, With<Button>),
+    >,
+    mut bindings: ResMut<ControlBindings>,
+    mut rebind_state: ResMut<RebindState>,
+) {
+    for (interaction, menu_button) in &mut interaction_query {
+        if *interaction == Interaction::Pressed {
+            match menu_button.action {
+                // While waiting, a click is the new binding rather than a new rebind
+                ButtonAction::Rebind(action) if rebind_state.waiting_for.is_none() => {
+                    rebind_state.waiting_for = Some(action);
+                }
+                ButtonAction::ResetControls => {
+                    *bindings = ControlBindings::default();
+                    bindings.save();
+                    rebind_state.waiting_for = None;
+                }
+                ButtonAction::Back => next_state.set(GameState::Settings),
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Binds the action waiting on the controls page to the next key, mouse or gamepad button pressed.
+fn capture_rebind_input(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    gamepad_buttons: Res<ButtonInput<GamepadButton>>,
+    button_query: Query<&Interaction, With<Button>>,
+    mut bindings: ResMut<ControlBindings>,
+    mut rebind_state: ResMut<RebindState>,
 ) {
+    // Skip the frame the rebind started so the click that started it isn't captured
+    if rebind_state.is_changed() {
+        return;
+    }
+    let Some(action) = rebind_state.waiting_for else {
+        return;
+    };
+
     if keyboard_input.just_pressed(KeyCode::Escape) {
+        rebind_state.waiting_for = None;
+        return;
+    }
+
+    if let Some(gamepad_button) = gamepad_buttons.get_just_pressed().next() {
+        bindings.rebind_gamepad(action, gamepad_button.button_type);
+        bindings.save();
+        rebind_state.waiting_for = None;
+        return;
+    }
+
+    // Clicks on the page's buttons (BACK, other rows) are menu navigation, not the new binding
+    let over_button = button_query.iter().any(|interaction| *interaction != Interaction::None);
+    let pressed = keyboard_input
+        .get_just_pressed()
+        .next()
+        .map(|key_code| InputBinding::Key(*key_code))
+        .or_else(|| {
+            mouse_input
+                .get_just_pressed()
+                .next()
+                .filter(|_| !over_button)
+                .map(|mouse_button| InputBinding::Mouse(*mouse_button))
+        });
+
+    if let Some(binding) = pressed {
+        bindings.rebind(action, binding);
+        bindings.save();
+        rebind_state.waiting_for = None;
+    }
+}
+
+fn update_binding_labels(
+    bindings: Res<ControlBindings>,
+    rebind_state: Res<RebindState>,
+    mut label_query: Query<(&BindingLabel, &mut Text)>,
+) {
+    if !bindings.is_changed() && !rebind_state.is_changed() {
+        return;
+    }
+
+    for (label, mut text) in label_query.iter_mut() {
+        if let Some(section) = text.sections.get_mut(0) {
+            section.value = if rebind_state.waiting_for == Some(label.action) {
+                "Press a button (Esc cancels)".to_string()
+            } else {
+                bindings.display_name(label.action)
+            };
+        }
+    }
+}
+
+fn unlocks_menu_system(
+    mut next_state: ResMut<NextState<GameState>>,
+    mut interaction_query: Query<
+        (&Interaction, &MenuButton),
+        (Changed<Interaction>, With<Button>),
+    >,
+    mut profile: ResMut<Profile>,
+) {
+    for (interaction, menu_button) in &mut interaction_query {
+        if *interaction == Interaction::Pressed {
+            match menu_button.action {
+                ButtonAction::BuyUnlock(unlock) => {
+                    let changed = if profile.has(unlock) {
+                        profile.equip(unlock)
+                    } else if profile.buy(unlock) {
+                        info!("Unlocked {}", unlock.definition().name);
+                        true
+                    } else {
+                        false
+                    };
+                    if changed {
+                        profile.save();
+                    }
+                }
+                ButtonAction::Back => next_state.set(GameState::MainMenu),
+                _ => {}
+            }
+        }
+    }
+}
+
+fn update_unlock_labels(
+    profile: Res<Profile>,
+    mut label_query: Query<(&UnlockLabel, &mut Text)>,
+    mut currency_query: Query<&mut Text, (With<CurrencyText>, Without<UnlockLabel>)>,
+) {
+    if !profile.is_changed() {
+        return;
+    }
+
+    for (label, mut text) in label_query.iter_mut() {
+        if let Some(section) = text.sections.get_mut(0) {
+            section.value = unlock_label(&profile, label.unlock);
+        }
+    }
+    for mut text in currency_query.iter_mut() {
+        if let Some(section) = text.sections.get_mut(0) {
+            section.value = format!("{} shards", profile.currency);
+        }
+    }
+}
+
+// Codex Menu
+fn setup_codex_menu(mut commands: Commands, profile: Res<Profile>) {
+    commands.spawn((
+        NodeBundle {
+            style: Style {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                flex_direction: FlexDirection::Column,
+                ..default()
+            },
+            background_color: Color::srgba(0.0, 0.1, 0.2, 0.9).into(),
+            ..default()
+        },
+        MenuUI,
+        MenuBackground,
+        PsychedelicMenuEffect { phase: 0.0, speed: 1.0 },
+    ))
+    .with_children(|parent| {
+        parent.spawn((
+            TextBundle::from_section(
+                "CODEX",
+                TextStyle {
+                    font_size: 60.0,
+                    color: Color::srgb(0.0, 1.0, 1.0),
+                    ..default()
+                },
+            )
+            .with_style(Style {
+                margin: UiRect::bottom(Val::Px(20.0)),
+                ..default()
+            }),
+            MenuTitle,
+            PsychedelicMenuEffect { phase: 0.0, speed: 2.5 },
+        ));
+
+        for definition in UNLOCKS.iter() {
+            let Some(zombie_type) = definition.unlock.codex_entry() else {
+                continue;
+            };
+            let (name, description) = if profile.has(definition.unlock) {
+                (definition.name, codex_description(&zombie_type))
+            } else {
+                ("???", "Unlock this entry from the unlock tree.")
+            };
+
+            parent.spawn((
+                NodeBundle {
+                    style: Style {
+                        width: Val::Px(700.0),
+                        margin: UiRect::all(Val::Px(6.0)),
+                        padding: UiRect::all(Val::Px(12.0)),
+                        flex_direction: FlexDirection::Column,
+                        border: UiRect::all(Val::Px(2.0)),
+                        ..default()
+                    },
+                    background_color: Color::srgba(0.1, 0.1, 0.3, 0.6).into(),
+                    border_color: Color::srgb(0.5, 0.5, 1.0).into(),
+                    ..default()
+                },
+                MenuUI,
+            ))
+            .with_children(|parent| {
+                parent.spawn((
+                    TextBundle::from_section(
+                        name,
+                        TextStyle {
+                            font_size: 24.0,
+                            color: Color::srgb(1.0, 1.0, 0.0),
+                            ..default()
+                        },
+                    ),
+                    MenuUI,
+                ));
+                parent.spawn((
+                    TextBundle::from_section(
+                        description,
+                        TextStyle {
+                            font_size: 18.0,
+                            color: Color::srgb(0.9, 0.9, 0.9),
+                            ..default()
+                        },
+                    ),
+                    MenuUI,
+                ));
+            });
+        }
+
+        parent.spawn((
+            NodeBundle {
+                style: Style {
+                    margin: UiRect::top(Val::Px(30.0)),
+                    ..default()
+                },
+                ..default()
+            },
+            MenuUI,
+        ))
+        .with_children(|parent| {
+            create_menu_button(parent, "BACK", ButtonAction::Back);
+        });
+    });
+}
+
+fn codex_menu_system(
+    mut next_state: ResMut<NextState<GameState>>,
+    mut interaction_query: Query<
+        (&Interaction, &MenuButton),
+        (Changed<Interaction>, With<Button>),
+    >,
+) {
+    for (interaction, menu_button) in &mut interaction_query {
+        if *interaction == Interaction::Pressed {
+            if let ButtonAction::Back = menu_button.action {
+                next_state.set(GameState::MainMenu);
+            }
+        }
+    }
+}
+
+fn cancel_rebind(mut rebind_state: ResMut<RebindState>) {
+    rebind_state.waiting_for = None;
+}
+
+fn handle_pause_input(
+    action_state: Res<ActionState<PlayerAction>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if action_state.just_pressed(&PlayerAction::Pause) {
         next_state.set(GameState::Paused);
     }
 }
 
 fn handle_unpause_input(
-    keyboard_input: Res<ButtonInput<KeyCode>>,
+    action_state: Res<ActionState<PlayerAction>>,
     mut next_state: ResMut<NextState<GameState>>,
 ) {
-    if keyboard_input.just_pressed(KeyCode::Escape) {
+    if action_state.just_pressed(&PlayerAction::Pause) {
         next_state.set(GameState::InGame);
     }
 }
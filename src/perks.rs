@@ -0,0 +1,425 @@
+use bevy::prelude::*;
+use rand::Rng;
+use crate::{
+    GameState, InRun, Player, WaveCompletedEvent,
+    menu::{ButtonAction, MenuButton, MenuUI},
+    physics::Jumper,
+    weapons::BulletModifiers,
+};
+
+const CARDS_PER_DRAFT: usize = 3;
+/// Weight multiplier for each owned perk a candidate synergizes with.
+const SYNERGY_WEIGHT_MULTIPLIER: f32 = 2.5;
+
+pub struct PerkPlugin;
+
+impl Plugin for PerkPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<RunPerks>()
+            .add_systems(Update, start_perk_draft.run_if(in_state(GameState::InGame)))
+            .add_systems(OnEnter(GameState::PerkDraft), setup_perk_draft)
+            .add_systems(Update, perk_draft_system.run_if(in_state(GameState::PerkDraft)))
+            .add_systems(OnExit(GameState::PerkDraft), cleanup_perk_draft)
+            .add_systems(OnExit(InRun), reset_run_perks);
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PerkKind {
+    Vitality,
+    PlatedArmor,
+    HeavyRounds,
+    Bruiser,
+    PiercingRounds,
+    ExtraJump,
+    Lifesteal,
+    ExplosiveKills,
+    ChainReaction,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PerkRarity {
+    Common,
+    Rare,
+    Epic,
+}
+
+impl PerkRarity {
+    fn weight(&self) -> f32 {
+        match self {
+            PerkRarity::Common => 60.0,
+            PerkRarity::Rare => 30.0,
+            PerkRarity::Epic => 10.0,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            PerkRarity::Common => "COMMON",
+            PerkRarity::Rare => "RARE",
+            PerkRarity::Epic => "EPIC",
+        }
+    }
+
+    pub fn color(&self) -> Color {
+        match self {
+            PerkRarity::Common => Color::srgb(0.7, 0.7, 0.8),
+            PerkRarity::Rare => Color::srgb(0.2, 0.6, 1.0),
+            PerkRarity::Epic => Color::srgb(1.0, 0.4, 1.0),
+        }
+    }
+}
+
+pub struct PerkDefinition {
+    pub kind: PerkKind,
+    pub name: &'static str,
+    pub description: &'static str,
+    pub rarity: PerkRarity,
+    pub max_stacks: u32,
+    /// Perk that must already be owned before this one is offered.
+    pub requires: Option<PerkKind>,
+    /// Owning any of these makes this perk more likely to be offered.
+    pub synergies: &'static [PerkKind],
+}
+
+pub const PERKS: [PerkDefinition; 9] = [
+    PerkDefinition {
+        kind: PerkKind::Vitality,
+        name: "Vitality",
+        description: "+25 max health",
+        rarity: PerkRarity::Common,
+        max_stacks: 4,
+        requires: None,
+        synergies: &[PerkKind::Lifesteal],
+    },
+    PerkDefinition {
+        kind: PerkKind::PlatedArmor,
+        name: "Plated Armor",
+        description: "+25 max armor, fully repaired",
+        rarity: PerkRarity::Common,
+        max_stacks: 4,
+        requires: None,
+        synergies: &[PerkKind::Vitality],
+    },
+    PerkDefinition {
+        kind: PerkKind::HeavyRounds,
+        name: "Heavy Rounds",
+        description: "+20% bullet damage",
+        rarity: PerkRarity::Common,
+        max_stacks: 5,
+        requires: None,
+        synergies: &[PerkKind::PiercingRounds],
+    },
+    PerkDefinition {
+        kind: PerkKind::Bruiser,
+        name: "Bruiser",
+        description: "+50% bullet knockback",
+        rarity: PerkRarity::Common,
+        max_stacks: 3,
+        requires: None,
+        synergies: &[PerkKind::HeavyRounds],
+    },
+    PerkDefinition {
+        kind: PerkKind::PiercingRounds,
+        name: "Piercing Rounds",
+        description: "Bullets pass through one more zombie",
+        rarity: PerkRarity::Rare,
+        max_stacks: 3,
+        requires: None,
+        synergies: &[PerkKind::HeavyRounds, PerkKind::ExplosiveKills],
+    },
+    PerkDefinition {
+        kind: PerkKind::ExtraJump,
+        name: "Extra Jump",
+        description: "One more jump in mid-air",
+        rarity: PerkRarity::Rare,
+        max_stacks: 2,
+        requires: None,
+        synergies: &[],
+    },
+    PerkDefinition {
+        kind: PerkKind::Lifesteal,
+        name: "Lifesteal",
+        description: "Heal 5% of damage dealt",
+        rarity: PerkRarity::Rare,
+        max_stacks: 3,
+        requires: None,
+        synergies: &[PerkKind::Vitality, PerkKind::HeavyRounds],
+    },
+    PerkDefinition {
+        kind: PerkKind::ExplosiveKills,
+        name: "Explosive Kills",
+        description: "Zombies killed by bullets explode",
+        rarity: PerkRarity::Epic,
+        max_stacks: 1,
+        requires: None,
+        synergies: &[PerkKind::PiercingRounds],
+    },
+    PerkDefinition {
+        kind: PerkKind::ChainReaction,
+        name: "Chain Reaction",
+        description: "Bigger kill explosions",
+        rarity: PerkRarity::Epic,
+        max_stacks: 2,
+        requires: Some(PerkKind::ExplosiveKills),
+        synergies: &[PerkKind::PiercingRounds],
+    },
+];
+
+impl PerkKind {
+    pub fn definition(&self) -> &'static PerkDefinition {
+        PERKS
+            .iter()
+            .find(|definition| definition.kind == *self)
+            .expect("every perk has a definition")
+    }
+}
+
+/// Perks taken so far this run, and the cards currently on offer.
+#[derive(Resource, Default)]
+pub struct RunPerks {
+    pub owned: Vec<PerkKind>,
+    pub offered: Vec<PerkKind>,
+    pub wave: u32,
+}
+
+impl RunPerks {
+    pub fn stacks(&self, kind: PerkKind) -> u32 {
+        self.owned.iter().filter(|owned| **owned == kind).count() as u32
+    }
+
+    /// Chance of `definition` being drawn, or zero if it's maxed out or locked.
+    fn weight(&self, definition: &PerkDefinition) -> f32 {
+        if self.stacks(definition.kind) >= definition.max_stacks {
+            return 0.0;
+        }
+        if definition.requires.is_some_and(|required| self.stacks(required) == 0) {
+            return 0.0;
+        }
+
+        let synergies = definition
+            .synergies
+            .iter()
+            .filter(|synergy| self.stacks(**synergy) > 0)
+            .count() as i32;
+        definition.rarity.weight() * SYNERGY_WEIGHT_MULTIPLIER.powi(synergies)
+    }
+
+    /// Draws up to `CARDS_PER_DRAFT` different perks, weighted by rarity and synergies.
+    fn draw_offer(&self) -> Vec<PerkKind> {
+        let mut rng = rand::thread_rng();
+        let mut candidates: Vec<(PerkKind, f32)> = PERKS
+            .iter()
+            .map(|definition| (definition.kind, self.weight(definition)))
+            .filter(|(_, weight)| *weight > 0.0)
+            .collect();
+        let mut offer = Vec::new();
+
+        while offer.len() < CARDS_PER_DRAFT && !candidates.is_empty() {
+            let total: f32 = candidates.iter().map(|(_, weight)| weight).sum();
+            let mut roll = rng.gen_range(0.0..total);
+            let index = candidates
+                .iter()
+                .position(|(_, weight)| {
+                    roll -= weight;
+                    roll < 0.0
+                })
+                .unwrap_or(candidates.len() - 1);
+            offer.push(candidates.swap_remove(index).0);
+        }
+
+        offer
+    }
+}
+
+pub fn apply_perk(kind: PerkKind, player: &mut Player, jumper: &mut Jumper, bullet_modifiers: &mut BulletModifiers) {
+    match kind {
+        PerkKind::Vitality => {
+            player.max_health += 25.0;
+            player.health = (player.health + 25.0).min(player.max_health);
+        }
+        PerkKind::PlatedArmor => {
+            player.max_armor += 25.0;
+            player.armor = player.max_armor;
+        }
+        PerkKind::HeavyRounds => bullet_modifiers.damage_multiplier += 0.2,
+        PerkKind::Bruiser => bullet_modifiers.knockback_multiplier += 0.5,
+        PerkKind::PiercingRounds => bullet_modifiers.pierce += 1,
+        PerkKind::ExtraJump => jumper.max_jumps += 1,
+        PerkKind::Lifesteal => player.lifesteal += 0.05,
+        PerkKind::ExplosiveKills => bullet_modifiers.explosion_radius = 3.0,
+        PerkKind::ChainReaction => bullet_modifiers.explosion_radius += 2.0,
+    }
+}
+
+#[derive(Component)]
+pub struct PerkDraftUI;
+
+fn start_perk_draft(
+    mut wave_events: EventReader<WaveCompletedEvent>,
+    mut run_perks: ResMut<RunPerks>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    for event in wave_events.read() {
+        let offer = run_perks.draw_offer();
+        if offer.is_empty() {
+            info!("Every perk is maxed out, skipping the draft");
+            continue;
+        }
+
+        run_perks.offered = offer;
+        run_perks.wave = event.wave;
+        next_state.set(GameState::PerkDraft);
+    }
+}
+
+fn setup_perk_draft(mut commands: Commands, run_perks: Res<RunPerks>) {
+    commands.spawn((
+        NodeBundle {
+            style: Style {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                flex_direction: FlexDirection::Column,
+                ..default()
+            },
+            background_color: Color::srgba(0.05, 0.0, 0.1, 0.85).into(),
+            ..default()
+        },
+        MenuUI,
+        PerkDraftUI,
+    ))
+    .with_children(|parent| {
+        parent.spawn(
+            TextBundle::from_section(
+                format!("WAVE {} CLEARED - CHOOSE A PERK", run_perks.wave),
+                TextStyle {
+                    font_size: 48.0,
+                    color: Color::srgb(1.0, 1.0, 0.0),
+                    ..default()
+                },
+            )
+            .with_style(Style {
+                margin: UiRect::bottom(Val::Px(40.0)),
+                ..default()
+            }),
+        );
+
+        parent
+            .spawn(NodeBundle {
+                style: Style {
+                    flex_direction: FlexDirection::Row,
+                    column_gap: Val::Px(30.0),
+                    ..default()
+                },
+                ..default()
+            })
+            .with_children(|parent| {
+                for (index, kind) in run_perks.offered.iter().enumerate() {
+                    create_perk_card(parent, index, kind.definition(), run_perks.stacks(*kind));
+                }
+            });
+    });
+}
+
+fn create_perk_card(parent: &mut ChildBuilder, index: usize, definition: &PerkDefinition, stacks: u32) {
+    let rarity_color = definition.rarity.color();
+
+    parent.spawn((
+        ButtonBundle {
+            style: Style {
+                width: Val::Px(260.0),
+                height: Val::Px(320.0),
+                padding: UiRect::all(Val::Px(20.0)),
+                border: UiRect::all(Val::Px(3.0)),
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::SpaceBetween,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            background_color: Color::srgba(0.2, 0.0, 0.4, 0.8).into(),
+            border_color: rarity_color.into(),
+            ..default()
+        },
+        MenuButton { action: ButtonAction::PickPerk(index) },
+        MenuUI,
+    ))
+    .with_children(|parent| {
+        parent.spawn(TextBundle::from_section(
+            definition.rarity.label(),
+            TextStyle {
+                font_size: 18.0,
+                color: rarity_color,
+                ..default()
+            },
+        ));
+        parent.spawn(TextBundle::from_section(
+            definition.name,
+            TextStyle {
+                font_size: 30.0,
+                color: Color::WHITE,
+                ..default()
+            },
+        ));
+        parent.spawn(
+            TextBundle::from_section(
+                definition.description,
+                TextStyle {
+                    font_size: 20.0,
+                    color: Color::srgb(0.85, 0.85, 0.85),
+                    ..default()
+                },
+            )
+            .with_text_justify(JustifyText::Center),
+        );
+        parent.spawn(TextBundle::from_section(
+            format!("Owned: {}/{}", stacks, definition.max_stacks),
+            TextStyle {
+                font_size: 16.0,
+                color: Color::srgb(0.6, 0.6, 0.6),
+                ..default()
+            },
+        ));
+    });
+}
+
+fn perk_draft_system(
+    mut next_state: ResMut<NextState<GameState>>,
+    interaction_query: Query<(&Interaction, &MenuButton), (Changed<Interaction>, With<Button>)>,
+    mut run_perks: ResMut<RunPerks>,
+    mut player_query: Query<(&mut Player, &mut Jumper)>,
+    mut bullet_modifiers: ResMut<BulletModifiers>,
+) {
+    for (interaction, menu_button) in interaction_query.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        let ButtonAction::PickPerk(index) = menu_button.action else {
+            continue;
+        };
+        let Some(&kind) = run_perks.offered.get(index) else {
+            continue;
+        };
+
+        if let Ok((mut player, mut jumper)) = player_query.get_single_mut() {
+            apply_perk(kind, &mut player, &mut jumper, &mut bullet_modifiers);
+        }
+        info!("Took perk {}", kind.definition().name);
+        run_perks.owned.push(kind);
+        run_perks.offered.clear();
+        next_state.set(GameState::InGame);
+        return;
+    }
+}
+
+fn cleanup_perk_draft(mut commands: Commands, ui_query: Query<Entity, With<PerkDraftUI>>) {
+    for entity in ui_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+fn reset_run_perks(mut run_perks: ResMut<RunPerks>, mut bullet_modifiers: ResMut<BulletModifiers>) {
+    *run_perks = RunPerks::default();
+    *bullet_modifiers = BulletModifiers::default();
+}
@@ -0,0 +1,31 @@
+use bevy::asset::ron;
+use bevy::log::warn;
+use serde::{de::DeserializeOwned, Serialize};
+use std::fs;
+
+/// Reads a RON file saved by `save_ron`, or `None` if it's missing or unreadable.
+pub fn load_ron<T: DeserializeOwned>(path: &str) -> Option<T> {
+    let contents = fs::read_to_string(path).ok()?;
+
+    match ron::from_str(&contents) {
+        Ok(value) => Some(value),
+        Err(error) => {
+            warn!("Ignoring invalid {}: {}", path, error);
+            None
+        }
+    }
+}
+
+pub fn save_ron<T: Serialize>(path: &str, value: &T) {
+    let contents = match ron::ser::to_string_pretty(value, ron::ser::PrettyConfig::default()) {
+        Ok(contents) => contents,
+        Err(error) => {
+            warn!("Failed to serialize {}: {}", path, error);
+            return;
+        }
+    };
+
+    if let Err(error) = fs::write(path, contents) {
+        warn!("Failed to save {}: {}", path, error);
+    }
+}
@@ -0,0 +1,579 @@
+use bevy::prelude::*;
+use bevy::input::mouse::{MouseMotion, MouseWheel};
+use bevy::render::view::screenshot::ScreenshotManager;
+use bevy::ui::RelativeCursorPosition;
+use bevy::window::PrimaryWindow;
+use leafwing_input_manager::prelude::*;
+use std::time::{SystemTime, UNIX_EPOCH};
+use crate::{
+    GameCamera, GameConfig, GameState,
+    camera::{fly_direction, CameraSettings},
+    controls::PlayerAction,
+    menu::{ButtonAction, MenuButton, MenuUI},
+    player::PlayerModel,
+    post_process::PostProcessSettings,
+    ui::HudRoot,
+};
+
+/// Screenshots are saved here, relative to the working directory.
+const SCREENSHOT_DIR: &str = "screenshots";
+const HIDE_PANEL_KEY: KeyCode = KeyCode::KeyH;
+const SCREENSHOT_KEY: KeyCode = KeyCode::F12;
+
+pub struct PhotoModePlugin;
+
+impl Plugin for PhotoModePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PhotoModeSettings>()
+            .add_systems(OnEnter(GameState::PhotoMode), (enter_photo_mode, setup_photo_mode_ui))
+            .add_systems(
+                Update,
+                (
+                    handle_photo_mode_input,
+                    photo_mode_buttons,
+                    drag_photo_sliders,
+                    move_photo_camera,
+                    apply_photo_mode,
+                    update_photo_slider_visuals,
+                    take_photo_mode_screenshot,
+                ).chain().run_if(in_state(GameState::PhotoMode)),
+            )
+            .add_systems(OnExit(GameState::PhotoMode), exit_photo_mode);
+    }
+}
+
+#[derive(Resource)]
+pub struct PhotoModeSettings {
+    /// Metres per second; sprint multiplies it.
+    pub move_speed: f32,
+    pub sprint_multiplier: f32,
+    pub look_sensitivity: f32,
+    /// Degrees per second while a roll key is held.
+    pub roll_speed: f32,
+    /// Degrees per scroll-wheel notch.
+    pub fov_step: f32,
+}
+
+impl Default for PhotoModeSettings {
+    fn default() -> Self {
+        Self {
+            move_speed: 4.0,
+            sprint_multiplier: 3.0,
+            look_sensitivity: 0.003,
+            roll_speed: 45.0,
+            fov_step: 5.0,
+        }
+    }
+}
+
+/// A value photo mode lets the player tweak.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PhotoControl {
+    Intensity,
+    PostEffects,
+    Fov,
+    Roll,
+}
+
+impl PhotoControl {
+    pub const ALL: [PhotoControl; 4] = [
+        PhotoControl::Intensity,
+        PhotoControl::PostEffects,
+        PhotoControl::Fov,
+        PhotoControl::Roll,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            PhotoControl::Intensity => "Psychedelic Intensity",
+            PhotoControl::PostEffects => "Post Effects",
+            PhotoControl::Fov => "Field of View",
+            PhotoControl::Roll => "Roll",
+        }
+    }
+
+    /// Slider range, with FOV and roll in degrees.
+    pub fn range(&self) -> (f32, f32) {
+        match self {
+            PhotoControl::Intensity => (0.0, 2.0),
+            PhotoControl::PostEffects => (0.0, 2.0),
+            PhotoControl::Fov => (30.0, 150.0),
+            PhotoControl::Roll => (-45.0, 45.0),
+        }
+    }
+}
+
+/// Free camera and effect values for the current photo mode session, plus what to restore on leaving.
+#[derive(Resource)]
+struct PhotoSession {
+    yaw: f32,
+    pitch: f32,
+    roll: f32,
+    fov: f32,
+    intensity: f32,
+    post_strength: f32,
+    panel_hidden: bool,
+    /// Frames until the screenshot is taken; the panel is hidden first so it isn't in the shot.
+    screenshot_countdown: u8,
+    saved_camera: Transform,
+    saved_fov: f32,
+    saved_intensity: f32,
+    saved_post_strength: f32,
+}
+
+impl PhotoSession {
+    fn value(&self, control: PhotoControl) -> f32 {
+        match control {
+            PhotoControl::Intensity => self.intensity,
+            PhotoControl::PostEffects => self.post_strength,
+            PhotoControl::Fov => self.fov,
+            PhotoControl::Roll => self.roll,
+        }
+    }
+
+    fn set_value(&mut self, control: PhotoControl, value: f32) {
+        let (min, max) = control.range();
+        let value = value.clamp(min, max);
+        match control {
+            PhotoControl::Intensity => self.intensity = value,
+            PhotoControl::PostEffects => self.post_strength = value,
+            PhotoControl::Fov => self.fov = value,
+            PhotoControl::Roll => self.roll = value,
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct PhotoModeUI;
+
+/// Draggable track for one photo mode control.
+#[derive(Component)]
+pub struct PhotoSlider {
+    pub control: PhotoControl,
+}
+
+#[derive(Component)]
+pub struct PhotoSliderFill {
+    pub control: PhotoControl,
+}
+
+#[derive(Component)]
+pub struct PhotoSliderValue {
+    pub control: PhotoControl,
+}
+
+fn perspective_fov(projection: &Projection) -> Option<f32> {
+    match projection {
+        Projection::Perspective(perspective) => Some(perspective.fov),
+        _ => None,
+    }
+}
+
+fn enter_photo_mode(
+    mut commands: Commands,
+    mut virtual_time: ResMut<Time<Virtual>>,
+    camera_query: Query<(&Transform, &Projection), With<GameCamera>>,
+    mut hud_query: Query<&mut Visibility, (With<HudRoot>, Without<PlayerModel>)>,
+    mut model_query: Query<&mut Visibility, (With<PlayerModel>, Without<HudRoot>)>,
+    config: Res<GameConfig>,
+    post_process_settings: Res<PostProcessSettings>,
+    camera_settings: Res<CameraSettings>,
+) {
+    // Stop the clock so particles, lights and shader animation hold still for the shot
+    virtual_time.pause();
+
+    for mut visibility in hud_query.iter_mut() {
+        *visibility = Visibility::Hidden;
+    }
+    // The player is part of the scene now the camera can leave their head
+    for mut visibility in model_query.iter_mut() {
+        *visibility = Visibility::Inherited;
+    }
+
+    let base_fov = camera_settings.fov_degrees.to_radians();
+    let (transform, fov) = camera_query
+        .get_single()
+        .map(|(transform, projection)| (*transform, perspective_fov(projection).unwrap_or(base_fov)))
+        .unwrap_or((Transform::default(), base_fov));
+    let (yaw, pitch, roll) = transform.rotation.to_euler(EulerRot::YXZ);
+
+    commands.insert_resource(PhotoSession {
+        yaw,
+        pitch,
+        roll: roll.to_degrees(),
+        fov: fov.to_degrees(),
+        intensity: config.psychedelic_intensity,
+        post_strength: post_process_settings.strength,
+        panel_hidden: false,
+        screenshot_countdown: 0,
+        saved_camera: transform,
+        saved_fov: fov,
+        saved_intensity: config.psychedelic_intensity,
+        saved_post_strength: post_process_settings.strength,
+    });
+}
+
+fn setup_photo_mode_ui(mut commands: Commands) {
+    commands.spawn((
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                right: Val::Px(20.0),
+                top: Val::Px(20.0),
+                width: Val::Px(360.0),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                padding: UiRect::all(Val::Px(16.0)),
+                border: UiRect::all(Val::Px(2.0)),
+                ..default()
+            },
+            background_color: Color::srgba(0.0, 0.0, 0.1, 0.75).into(),
+            border_color: Color::srgb(0.0, 1.0, 1.0).into(),
+            ..default()
+        },
+        MenuUI,
+        PhotoModeUI,
+    ))
+    .with_children(|parent| {
+        parent.spawn(TextBundle::from_section(
+            "PHOTO MODE",
+            TextStyle {
+                font_size: 36.0,
+                color: Color::srgb(0.0, 1.0, 1.0),
+                ..default()
+            },
+        ));
+        parent.spawn(
+            TextBundle::from_section(
+                "Move to fly, hold right mouse to look\nRoll keys tilt, scroll to zoom\nH hide panel, F12 screenshot",
+                TextStyle {
+                    font_size: 16.0,
+                    color: Color::srgb(0.8, 0.8, 0.8),
+                    ..default()
+                },
+            )
+            .with_style(Style {
+                margin: UiRect::vertical(Val::Px(10.0)),
+                ..default()
+            }),
+        );
+
+        for control in PhotoControl::ALL {
+            create_photo_slider(parent, control);
+        }
+
+        create_photo_button(parent, "SAVE SCREENSHOT", ButtonAction::SaveScreenshot);
+        create_photo_button(parent, "BACK", ButtonAction::Back);
+    });
+}
+
+fn create_photo_slider(parent: &mut ChildBuilder, control: PhotoControl) {
+    parent.spawn(NodeBundle {
+        style: Style {
+            width: Val::Percent(100.0),
+            justify_content: JustifyContent::SpaceBetween,
+            margin: UiRect::top(Val::Px(8.0)),
+            ..default()
+        },
+        ..default()
+    })
+    .with_children(|parent| {
+        parent.spawn(TextBundle::from_section(
+            control.label(),
+            TextStyle {
+                font_size: 18.0,
+                color: Color::srgb(1.0, 1.0, 1.0),
+                ..default()
+            },
+        ));
+        parent.spawn((
+            TextBundle::from_section(
+                "",
+                TextStyle {
+                    font_size: 18.0,
+                    color: Color::srgb(0.0, 1.0, 0.0),
+                    ..default()
+                },
+            ),
+            PhotoSliderValue { control },
+        ));
+    });
+
+    parent.spawn((
+        NodeBundle {
+            style: Style {
+                width: Val::Percent(100.0),
+                height: Val::Px(16.0),
+                margin: UiRect::top(Val::Px(4.0)),
+                border: UiRect::all(Val::Px(2.0)),
+                ..default()
+            },
+            background_color: Color::srgba(0.1, 0.1, 0.3, 0.8).into(),
+            border_color: Color::srgb(0.5, 0.5, 1.0).into(),
+            ..default()
+        },
+        Interaction::default(),
+        RelativeCursorPosition::default(),
+        PhotoSlider { control },
+    ))
+    .with_children(|parent| {
+        parent.spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(0.0),
+                    height: Val::Percent(100.0),
+                    ..default()
+                },
+                background_color: Color::srgb(1.0, 0.0, 1.0).into(),
+                ..default()
+            },
+            PhotoSliderFill { control },
+        ));
+    });
+}
+
+fn create_photo_button(parent: &mut ChildBuilder, text: &str, action: ButtonAction) {
+    parent.spawn((
+        ButtonBundle {
+            style: Style {
+                width: Val::Percent(100.0),
+                height: Val::Px(44.0),
+                margin: UiRect::top(Val::Px(12.0)),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                border: UiRect::all(Val::Px(2.0)),
+                ..default()
+            },
+            background_color: Color::srgba(0.2, 0.0, 0.4, 0.8).into(),
+            border_color: Color::srgb(1.0, 0.0, 1.0).into(),
+            ..default()
+        },
+        MenuButton { action },
+        MenuUI,
+    ))
+    .with_children(|parent| {
+        parent.spawn(TextBundle::from_section(
+            text,
+            TextStyle {
+                font_size: 20.0,
+                color: Color::srgb(1.0, 1.0, 1.0),
+                ..default()
+            },
+        ));
+    });
+}
+
+fn handle_photo_mode_input(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    action_state: Res<ActionState<PlayerAction>>,
+    mut session: ResMut<PhotoSession>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if action_state.just_pressed(&PlayerAction::Pause) {
+        next_state.set(GameState::Paused);
+        return;
+    }
+    if keyboard_input.just_pressed(HIDE_PANEL_KEY) {
+        session.panel_hidden = !session.panel_hidden;
+    }
+    if keyboard_input.just_pressed(SCREENSHOT_KEY) && session.screenshot_countdown == 0 {
+        session.screenshot_countdown = 2;
+    }
+}
+
+fn photo_mode_buttons(
+    interaction_query: Query<(&Interaction, &MenuButton), (Changed<Interaction>, With<Button>)>,
+    mut session: ResMut<PhotoSession>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    for (interaction, menu_button) in interaction_query.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        match menu_button.action {
+            ButtonAction::SaveScreenshot if session.screenshot_countdown == 0 => session.screenshot_countdown = 2,
+            ButtonAction::Back => next_state.set(GameState::Paused),
+            _ => {}
+        }
+    }
+}
+
+fn drag_photo_sliders(
+    slider_query: Query<(&Interaction, &RelativeCursorPosition, &PhotoSlider)>,
+    mut session: ResMut<PhotoSession>,
+) {
+    for (interaction, cursor_position, slider) in slider_query.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        let Some(position) = cursor_position.normalized else {
+            continue;
+        };
+        let (min, max) = slider.control.range();
+        session.set_value(slider.control, min + position.x.clamp(0.0, 1.0) * (max - min));
+    }
+}
+
+/// Flies the detached camera; uses real time since the game clock is paused.
+fn move_photo_camera(
+    mut session: ResMut<PhotoSession>,
+    settings: Res<PhotoModeSettings>,
+    camera_settings: Res<CameraSettings>,
+    action_state: Res<ActionState<PlayerAction>>,
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    mut mouse_motion: EventReader<MouseMotion>,
+    mut mouse_wheel: EventReader<MouseWheel>,
+    mut camera_query: Query<&mut Transform, With<GameCamera>>,
+    real_time: Res<Time<Real>>,
+) {
+    let dt = real_time.delta_seconds();
+
+    // Look only while right mouse is held, so the cursor stays free for the sliders
+    let mut look = Vec2::ZERO;
+    for motion in mouse_motion.read() {
+        look += motion.delta;
+    }
+    if mouse_input.pressed(MouseButton::Right) {
+        session.yaw -= look.x * settings.look_sensitivity;
+        session.pitch = camera_settings.clamp_pitch(session.pitch - look.y * settings.look_sensitivity);
+    }
+
+    let mut roll = 0.0;
+    if action_state.pressed(&PlayerAction::RollLeft) {
+        roll += 1.0;
+    }
+    if action_state.pressed(&PlayerAction::RollRight) {
+        roll -= 1.0;
+    }
+    let new_roll = session.roll + roll * settings.roll_speed * dt;
+    session.set_value(PhotoControl::Roll, new_roll);
+
+    // Some devices send zero-length scroll events, which mustn't zoom
+    for wheel in mouse_wheel.read().filter(|wheel| wheel.y != 0.0) {
+        let new_fov = session.fov - wheel.y.signum() * settings.fov_step;
+        session.set_value(PhotoControl::Fov, new_fov);
+    }
+
+    let Ok(mut transform) = camera_query.get_single_mut() else {
+        return;
+    };
+    let heading = Quat::from_axis_angle(Vec3::Y, session.yaw) * Quat::from_axis_angle(Vec3::X, session.pitch);
+    let forward = heading * Vec3::NEG_Z;
+    let right = heading * Vec3::X;
+
+    let direction = fly_direction(&action_state, forward, right);
+
+    let mut speed = settings.move_speed;
+    if action_state.pressed(&PlayerAction::Sprint) {
+        speed *= settings.sprint_multiplier;
+    }
+    transform.translation += direction * speed * dt;
+}
+
+fn apply_photo_mode(
+    session: Res<PhotoSession>,
+    mut camera_query: Query<(&mut Transform, &mut Projection), With<GameCamera>>,
+    mut config: ResMut<GameConfig>,
+    mut post_process_settings: ResMut<PostProcessSettings>,
+) {
+    if let Ok((mut transform, mut projection)) = camera_query.get_single_mut() {
+        transform.rotation = Quat::from_euler(EulerRot::YXZ, session.yaw, session.pitch, session.roll.to_radians());
+        if let Projection::Perspective(perspective) = projection.as_mut() {
+            perspective.fov = session.fov.to_radians();
+        }
+    }
+
+    config.psychedelic_intensity = session.intensity;
+    post_process_settings.strength = session.post_strength;
+}
+
+fn update_photo_slider_visuals(
+    session: Res<PhotoSession>,
+    mut panel_query: Query<&mut Visibility, With<PhotoModeUI>>,
+    mut fill_query: Query<(&PhotoSliderFill, &mut Style)>,
+    mut value_query: Query<(&PhotoSliderValue, &mut Text)>,
+) {
+    let hidden = session.panel_hidden || session.screenshot_countdown > 0;
+    for mut visibility in panel_query.iter_mut() {
+        *visibility = if hidden { Visibility::Hidden } else { Visibility::Inherited };
+    }
+
+    for (fill, mut style) in fill_query.iter_mut() {
+        let (min, max) = fill.control.range();
+        let fraction = (session.value(fill.control) - min) / (max - min);
+        style.width = Val::Percent(fraction * 100.0);
+    }
+
+    for (value, mut text) in value_query.iter_mut() {
+        let current = session.value(value.control);
+        text.sections[0].value = match value.control {
+            PhotoControl::Fov | PhotoControl::Roll => format!("{:.0}°", current),
+            _ => format!("{:.2}", current),
+        };
+    }
+}
+
+fn take_photo_mode_screenshot(
+    mut session: ResMut<PhotoSession>,
+    mut screenshot_manager: ResMut<ScreenshotManager>,
+    window_query: Query<Entity, With<PrimaryWindow>>,
+) {
+    if session.screenshot_countdown == 0 {
+        return;
+    }
+    session.screenshot_countdown -= 1;
+    // The panel was hidden last frame, so this frame renders without it
+    if session.screenshot_countdown > 0 {
+        return;
+    }
+    let Ok(window) = window_query.get_single() else {
+        return;
+    };
+
+    if let Err(error) = std::fs::create_dir_all(SCREENSHOT_DIR) {
+        warn!("Failed to create {}: {}", SCREENSHOT_DIR, error);
+        return;
+    }
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_millis());
+    let path = format!("{}/void_{}.png", SCREENSHOT_DIR, timestamp);
+
+    match screenshot_manager.save_screenshot_to_disk(window, &path) {
+        Ok(()) => info!("Saved screenshot to {}", path),
+        Err(error) => warn!("Failed to take screenshot: {}", error),
+    }
+}
+
+fn exit_photo_mode(
+    mut commands: Commands,
+    session: Option<Res<PhotoSession>>,
+    mut virtual_time: ResMut<Time<Virtual>>,
+    ui_query: Query<Entity, With<PhotoModeUI>>,
+    mut hud_query: Query<&mut Visibility, With<HudRoot>>,
+    mut camera_query: Query<(&mut Transform, &mut Projection), With<GameCamera>>,
+    mut config: ResMut<GameConfig>,
+    mut post_process_settings: ResMut<PostProcessSettings>,
+) {
+    virtual_time.unpause();
+
+    for entity in ui_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    for mut visibility in hud_query.iter_mut() {
+        *visibility = Visibility::Inherited;
+    }
+
+    // Put back whatever the shot changed; the player model is re-hidden by the camera mode
+    if let Some(session) = session {
+        if let Ok((mut transform, mut projection)) = camera_query.get_single_mut() {
+            *transform = session.saved_camera;
+            if let Projection::Perspective(perspective) = projection.as_mut() {
+                perspective.fov = session.saved_fov;
+            }
+        }
+        config.psychedelic_intensity = session.saved_intensity;
+        post_process_settings.strength = session.saved_post_strength;
+    }
+    commands.remove_resource::<PhotoSession>();
+}
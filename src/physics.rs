@@ -1,42 +1,78 @@
 use bevy::prelude::*;
-use crate::{GameState, Player, zombies::Zombie};
+use bevy::app::RunFixedMainLoop;
+use bevy::ecs::system::SystemParam;
+use bevy::utils::{HashMap, HashSet};
+use bevy::time::run_fixed_main_schedule;
+use leafwing_input_manager::prelude::*;
+use crate::{GameState, Player, PlayerDamageEvent, controls::PlayerAction, zombies::Zombie};
+
+/// Rate of the fixed physics step.
+pub const PHYSICS_HZ: f64 = 60.0;
+
+/// Per-step factors such as `RigidBody::drag` are tuned against this rate.
+const REFERENCE_HZ: f32 = 60.0;
+
+/// Colliders closer than this count as touching, so resting contacts don't flicker.
+const CONTACT_SKIN: f32 = 0.05;
+
+/// Lightest mass used when integrating, so a body configured with zero mass can't get infinite velocity.
+const MIN_BODY_MASS: f32 = 0.01;
 
 pub struct PhysicsPlugin;
 
 impl Plugin for PhysicsPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<PhysicsSettings>()
-            .add_systems(Update, (
+            .init_resource::<ActiveCollisions>()
+            .init_resource::<SpatialHash>()
+            .add_event::<CollisionStarted>()
+            .add_event::<CollisionOngoing>()
+            .add_event::<CollisionEnded>()
+            .insert_resource(Time::<Fixed>::from_hz(PHYSICS_HZ))
+            .add_systems(FixedFirst, restore_physics_transforms)
+            .add_systems(FixedUpdate, (
+                move_platforms,
+                carry_platform_riders,
                 apply_gravity,
                 apply_physics_movement,
-                handle_jumping,
-                player_zombie_collision,
                 zombie_zombie_collision,
-                check_arena_bounds,
+                player_zombie_separation,
+                resolve_environment_collisions,
                 apply_ground_detection,
+                snap_to_ground,
+                apply_jump_pads,
                 apply_friction,
-                update_collision_events,
-            ).run_if(in_state(GameState::InGame)));
+                update_spatial_hash,
+                detect_collisions,
+                player_zombie_collision,
+            ).chain().run_if(in_state(GameState::InGame)))
+            .add_systems(FixedLast, record_physics_transforms)
+            .add_systems(RunFixedMainLoop, interpolate_transforms.after(run_fixed_main_schedule))
+            .add_systems(Update, handle_jumping.run_if(in_state(GameState::InGame)));
     }
 }
 
 #[derive(Resource)]
 pub struct PhysicsSettings {
     pub gravity: f32,
-    pub ground_level: f32,
     pub jump_force: f32,
     pub player_damage_cooldown: f32,
     pub collision_damping: f32,
+    /// How much of the player/zombie overlap is corrected each step (0-1).
+    pub body_separation: f32,
+    /// Scales how far zombies shove the player; 0 makes the player immovable, 1 is purely mass-weighted.
+    pub player_pushback: f32,
 }
 
 impl Default for PhysicsSettings {
     fn default() -> Self {
         Self {
             gravity: -30.0,
-            ground_level: 0.0,
             jump_force: 15.0,
             player_damage_cooldown: 1.0, // 1 second between damage
             collision_damping: 0.5,
+            body_separation: 0.8,
+            player_pushback: 1.0,
         }
     }
 }
@@ -49,6 +85,10 @@ pub struct RigidBody {
     pub restitution: f32, // Bounciness
     pub drag: f32,
     pub is_kinematic: bool, // If true, not affected by forces
+    /// Force summed from `apply_force` calls, consumed by the next physics step.
+    pub accumulated_force: Vec3,
+    /// Position a kinematic body moves to on the next physics step.
+    pub kinematic_target: Option<Vec3>,
 }
 
 impl Default for RigidBody {
@@ -60,13 +100,55 @@ impl Default for RigidBody {
             restitution: 0.1,
             drag: 0.98,
             is_kinematic: false,
+            accumulated_force: Vec3::ZERO,
+            kinematic_target: None,
         }
     }
 }
 
+impl RigidBody {
+    /// Adds a continuous force (in newtons) for the next physics step.
+    pub fn apply_force(&mut self, force: Vec3) {
+        if !self.is_kinematic {
+            self.accumulated_force += force;
+        }
+    }
+
+    /// Instantly changes velocity by `impulse / mass`.
+    pub fn apply_impulse(&mut self, impulse: Vec3) {
+        if !self.is_kinematic {
+            self.velocity += impulse * self.inverse_mass();
+        }
+    }
+
+    pub fn inverse_mass(&self) -> f32 {
+        1.0 / self.mass.max(MIN_BODY_MASS)
+    }
+
+    /// Pushes a body at `position` away from `center`, with the impulse falling off linearly to zero at `radius`.
+    pub fn apply_explosion(&mut self, position: Vec3, center: Vec3, impulse: f32, radius: f32) {
+        let offset = position - center;
+        let distance = offset.length();
+        if distance >= radius {
+            return;
+        }
+
+        // Bias upwards so bodies on the ground get launched rather than slid
+        let direction = (offset.normalize_or_zero() + Vec3::Y * 0.5).normalize();
+        let falloff = 1.0 - distance / radius;
+        self.apply_impulse(direction * impulse * falloff);
+    }
+
+    /// Moves a kinematic body to `target` on the next physics step, deriving its velocity from the motion.
+    pub fn set_kinematic_target(&mut self, target: Vec3) {
+        self.kinematic_target = Some(target);
+    }
+}
+
+/// A `Collider` without a `RigidBody` is static environment geometry that bodies collide against.
 #[derive(Component)]
 pub struct Collider {
-    pub radius: f32,
+    pub shape: ColliderShape,
     pub collision_layer: CollisionLayer,
     pub collision_mask: u32, // Which layers this collides with
 }
@@ -74,13 +156,729 @@ pub struct Collider {
 impl Default for Collider {
     fn default() -> Self {
         Self {
-            radius: 0.5,
+            shape: ColliderShape::Sphere { radius: 0.5 },
             collision_layer: CollisionLayer::Default,
             collision_mask: 0b11111111, // Collides with all layers by default
         }
     }
 }
 
+impl Collider {
+    /// Static level geometry (walls, pillars, ramps) that bodies collide against.
+    pub fn environment(shape: ColliderShape) -> Self {
+        Self {
+            shape,
+            collision_layer: CollisionLayer::Environment,
+            collision_mask: CollisionLayer::Player.mask() | CollisionLayer::Zombie.mask() | CollisionLayer::Bullet.mask() | CollisionLayer::Debris.mask(),
+        }
+    }
+
+    /// Whether the two colliders' layers and masks allow them to interact.
+    pub fn interacts_with(&self, other: &Collider) -> bool {
+        self.collision_mask & other.collision_layer.mask() != 0
+            && other.collision_mask & self.collision_layer.mask() != 0
+    }
+
+    /// Radius of a sphere around the entity origin that contains the whole shape.
+    pub fn bounding_radius(&self) -> f32 {
+        self.shape.bounding_radius()
+    }
+
+    /// Half size of the world-space AABB around the shape at the given rotation.
+    pub fn half_extents(&self, rotation: Quat) -> Vec3 {
+        self.shape.half_extents(rotation)
+    }
+}
+
+/// Collision geometry, centered on the entity's translation.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ColliderShape {
+    Sphere { radius: f32 },
+    /// Vertical capsule: a segment of `2 * half_height` along local Y, swept by `radius`.
+    Capsule { radius: f32, half_height: f32 },
+    /// Axis-aligned box that ignores the entity's rotation.
+    Aabb { half_extents: Vec3 },
+    /// Box that follows the entity's rotation.
+    Obb { half_extents: Vec3 },
+}
+
+impl ColliderShape {
+    pub fn bounding_radius(&self) -> f32 {
+        match *self {
+            ColliderShape::Sphere { radius } => radius,
+            ColliderShape::Capsule { radius, half_height } => radius + half_height,
+            ColliderShape::Aabb { half_extents } | ColliderShape::Obb { half_extents } => half_extents.length(),
+        }
+    }
+
+    pub fn half_extents(&self, rotation: Quat) -> Vec3 {
+        match *self {
+            ColliderShape::Sphere { radius } => Vec3::splat(radius),
+            ColliderShape::Capsule { radius, half_height } => {
+                let axis = (rotation * Vec3::Y * half_height).abs();
+                axis + Vec3::splat(radius)
+            }
+            ColliderShape::Aabb { half_extents } => half_extents,
+            ColliderShape::Obb { half_extents } => {
+                let axes = Mat3::from_quat(rotation);
+                axes.x_axis.abs() * half_extents.x
+                    + axes.y_axis.abs() * half_extents.y
+                    + axes.z_axis.abs() * half_extents.z
+            }
+        }
+    }
+
+    /// The shape grown by `margin` in every direction.
+    pub fn inflated(self, margin: f32) -> ColliderShape {
+        match self {
+            ColliderShape::Sphere { radius } => ColliderShape::Sphere { radius: radius + margin },
+            ColliderShape::Capsule { radius, half_height } => ColliderShape::Capsule { radius: radius + margin, half_height },
+            ColliderShape::Aabb { half_extents } => ColliderShape::Aabb { half_extents: half_extents + margin },
+            ColliderShape::Obb { half_extents } => ColliderShape::Obb { half_extents: half_extents + margin },
+        }
+    }
+
+    fn to_world(self, transform: &Transform) -> WorldShape {
+        let center = transform.translation;
+        match self {
+            ColliderShape::Sphere { radius } => WorldShape::Round { a: center, b: center, radius },
+            ColliderShape::Capsule { radius, half_height } => {
+                let offset = transform.rotation * Vec3::Y * half_height;
+                WorldShape::Round { a: center - offset, b: center + offset, radius }
+            }
+            ColliderShape::Aabb { half_extents } => WorldShape::Box {
+                center,
+                axes: [Vec3::X, Vec3::Y, Vec3::Z],
+                half_extents,
+            },
+            ColliderShape::Obb { half_extents } => WorldShape::Box {
+                center,
+                axes: [transform.rotation * Vec3::X, transform.rotation * Vec3::Y, transform.rotation * Vec3::Z],
+                half_extents,
+            },
+        }
+    }
+}
+
+/// Result of a narrowphase test between two colliders.
+#[derive(Clone, Copy, Debug)]
+pub struct Contact {
+    /// Approximate world-space point of contact.
+    pub point: Vec3,
+    /// Unit normal pointing from the first collider towards the second.
+    pub normal: Vec3,
+    /// Overlap depth along `normal`.
+    pub penetration: f32,
+}
+
+/// Computes the contact between two colliders, or `None` if they don't overlap.
+pub fn compute_contact(
+    collider_a: &Collider,
+    transform_a: &Transform,
+    collider_b: &Collider,
+    transform_b: &Transform,
+) -> Option<Contact> {
+    let max_distance = collider_a.bounding_radius() + collider_b.bounding_radius();
+    if transform_a.translation.distance_squared(transform_b.translation) > max_distance * max_distance {
+        return None;
+    }
+
+    contact_between(collider_a.shape, transform_a, collider_b.shape, transform_b)
+}
+
+fn contact_between(
+    shape_a: ColliderShape,
+    transform_a: &Transform,
+    shape_b: ColliderShape,
+    transform_b: &Transform,
+) -> Option<Contact> {
+    let shape_a = shape_a.to_world(transform_a);
+    let shape_b = shape_b.to_world(transform_b);
+
+    match (shape_a, shape_b) {
+        (WorldShape::Round { a: a0, b: a1, radius: ra }, WorldShape::Round { a: b0, b: b1, radius: rb }) => {
+            let (pa, pb) = closest_points_segment_segment(a0, a1, b0, b1);
+            round_contact(pa, ra, pb, rb)
+        }
+        (WorldShape::Round { a, b, radius }, WorldShape::Box { center, axes, half_extents }) => {
+            round_box_contact(a, b, radius, center, axes, half_extents)
+        }
+        (WorldShape::Box { center, axes, half_extents }, WorldShape::Round { a, b, radius }) => {
+            round_box_contact(a, b, radius, center, axes, half_extents).map(|contact| Contact {
+                normal: -contact.normal,
+                ..contact
+            })
+        }
+        (
+            WorldShape::Box { center: ca, axes: axes_a, half_extents: ha },
+            WorldShape::Box { center: cb, axes: axes_b, half_extents: hb },
+        ) => box_box_contact(ca, axes_a, ha, cb, axes_b, hb),
+    }
+}
+
+/// Collider geometry resolved into world space. Spheres are capsules with a zero-length segment.
+#[derive(Clone, Copy)]
+enum WorldShape {
+    Round { a: Vec3, b: Vec3, radius: f32 },
+    Box { center: Vec3, axes: [Vec3; 3], half_extents: Vec3 },
+}
+
+fn round_contact(point_a: Vec3, radius_a: f32, point_b: Vec3, radius_b: f32) -> Option<Contact> {
+    let delta = point_b - point_a;
+    let distance = delta.length();
+    let penetration = radius_a + radius_b - distance;
+    if penetration <= 0.0 {
+        return None;
+    }
+
+    // Coincident centers have no meaningful direction, so push apart vertically
+    let normal = if distance > f32::EPSILON { delta / distance } else { Vec3::Y };
+
+    Some(Contact {
+        point: point_a + normal * (radius_a - penetration * 0.5),
+        normal,
+        penetration,
+    })
+}
+
+fn closest_point_on_segment(a: Vec3, b: Vec3, point: Vec3) -> Vec3 {
+    let ab = b - a;
+    let length_squared = ab.length_squared();
+    if length_squared <= f32::EPSILON {
+        return a;
+    }
+    let t = ((point - a).dot(ab) / length_squared).clamp(0.0, 1.0);
+    a + ab * t
+}
+
+fn closest_points_segment_segment(p1: Vec3, q1: Vec3, p2: Vec3, q2: Vec3) -> (Vec3, Vec3) {
+    let d1 = q1 - p1;
+    let d2 = q2 - p2;
+    let r = p1 - p2;
+    let a = d1.length_squared();
+    let e = d2.length_squared();
+    let f = d2.dot(r);
+
+    if a <= f32::EPSILON && e <= f32::EPSILON {
+        return (p1, p2);
+    }
+    if a <= f32::EPSILON {
+        return (p1, p2 + d2 * (f / e).clamp(0.0, 1.0));
+    }
+
+    let c = d1.dot(r);
+    if e <= f32::EPSILON {
+        return (p1 + d1 * (-c / a).clamp(0.0, 1.0), p2);
+    }
+
+    let b = d1.dot(d2);
+    let denominator = a * e - b * b;
+    let mut s = if denominator > f32::EPSILON {
+        ((b * f - c * e) / denominator).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    let mut t = (b * s + f) / e;
+    if t < 0.0 {
+        t = 0.0;
+        s = (-c / a).clamp(0.0, 1.0);
+    } else if t > 1.0 {
+        t = 1.0;
+        s = ((b - c) / a).clamp(0.0, 1.0);
+    }
+
+    (p1 + d1 * s, p2 + d2 * t)
+}
+
+fn closest_point_on_box(center: Vec3, axes: [Vec3; 3], half_extents: Vec3, point: Vec3) -> Vec3 {
+    let offset = point - center;
+    let mut closest = center;
+    for i in 0..3 {
+        let distance = offset.dot(axes[i]).clamp(-half_extents[i], half_extents[i]);
+        closest += axes[i] * distance;
+    }
+    closest
+}
+
+fn round_box_contact(
+    a: Vec3,
+    b: Vec3,
+    radius: f32,
+    center: Vec3,
+    axes: [Vec3; 3],
+    half_extents: Vec3,
+) -> Option<Contact> {
+    // Distance from a point on the segment to a convex box is convex along the segment,
+    // so a ternary search finds the segment point closest to the box
+    let distance_at = |t: f32| {
+        let point = a.lerp(b, t);
+        point.distance_squared(closest_point_on_box(center, axes, half_extents, point))
+    };
+    let (mut low, mut high) = (0.0_f32, 1.0_f32);
+    if a != b {
+        for _ in 0..24 {
+            let third = (high - low) / 3.0;
+            if distance_at(low + third) < distance_at(high - third) {
+                high -= third;
+            } else {
+                low += third;
+            }
+        }
+    }
+    let segment_point = a.lerp(b, (low + high) * 0.5);
+    let box_point = closest_point_on_box(center, axes, half_extents, segment_point);
+
+    let delta = box_point - segment_point;
+    let distance = delta.length();
+    if distance > f32::EPSILON {
+        let penetration = radius - distance;
+        if penetration <= 0.0 {
+            return None;
+        }
+        return Some(Contact { point: box_point, normal: delta / distance, penetration });
+    }
+
+    // The segment point is inside the box: push out through the nearest face
+    let local = segment_point - center;
+    let mut best_axis = 0;
+    let mut best_depth = f32::MAX;
+    for i in 0..3 {
+        let depth = half_extents[i] - local.dot(axes[i]).abs();
+        if depth < best_depth {
+            best_depth = depth;
+            best_axis = i;
+        }
+    }
+    let side = local.dot(axes[best_axis]).signum();
+    let normal = -axes[best_axis] * if side == 0.0 { 1.0 } else { side };
+
+    Some(Contact {
+        point: segment_point,
+        normal,
+        penetration: best_depth + radius,
+    })
+}
+
+fn box_box_contact(
+    center_a: Vec3,
+    axes_a: [Vec3; 3],
+    half_a: Vec3,
+    center_b: Vec3,
+    axes_b: [Vec3; 3],
+    half_b: Vec3,
+) -> Option<Contact> {
+    let offset = center_b - center_a;
+    let mut best_normal = Vec3::Y;
+    let mut best_penetration = f32::MAX;
+
+    let mut test_axis = |axis: Vec3| -> bool {
+        let length = axis.length();
+        // Skip degenerate cross products of parallel edges
+        if length < 1e-4 {
+            return true;
+        }
+        let axis = axis / length;
+        let projected_a: f32 = (0..3).map(|i| axes_a[i].dot(axis).abs() * half_a[i]).sum();
+        let projected_b: f32 = (0..3).map(|i| axes_b[i].dot(axis).abs() * half_b[i]).sum();
+        let distance = offset.dot(axis);
+        let penetration = projected_a + projected_b - distance.abs();
+        if penetration <= 0.0 {
+            return false;
+        }
+        if penetration < best_penetration {
+            best_penetration = penetration;
+            best_normal = if distance < 0.0 { -axis } else { axis };
+        }
+        true
+    };
+
+    // Separating axis theorem: face normals of both boxes plus all edge cross products
+    for i in 0..3 {
+        if !test_axis(axes_a[i]) || !test_axis(axes_b[i]) {
+            return None;
+        }
+    }
+    for i in 0..3 {
+        for j in 0..3 {
+            if !test_axis(axes_a[i].cross(axes_b[j])) {
+                return None;
+            }
+        }
+    }
+
+    let point_on_a = closest_point_on_box(center_a, axes_a, half_a, center_b);
+    let point_on_b = closest_point_on_box(center_b, axes_b, half_b, center_a);
+
+    Some(Contact {
+        point: (point_on_a + point_on_b) * 0.5,
+        normal: best_normal,
+        penetration: best_penetration,
+    })
+}
+
+impl WorldShape {
+    /// Distance from `point` to the shape's surface (zero inside) and the closest surface point.
+    fn distance_to_point(&self, point: Vec3) -> (f32, Vec3) {
+        match *self {
+            WorldShape::Round { a, b, radius } => {
+                let on_segment = closest_point_on_segment(a, b, point);
+                let offset = point - on_segment;
+                let distance = offset.length();
+                if distance <= radius {
+                    (0.0, point)
+                } else {
+                    (distance - radius, on_segment + offset / distance * radius)
+                }
+            }
+            WorldShape::Box { center, axes, half_extents } => {
+                let closest = closest_point_on_box(center, axes, half_extents, point);
+                (point.distance(closest), closest)
+            }
+        }
+    }
+
+    /// Travel distance along a unit `direction` until the ray enters the shape.
+    fn ray_distance(&self, origin: Vec3, direction: Vec3) -> Option<(f32, Vec3)> {
+        match *self {
+            WorldShape::Round { a, b, radius } => {
+                // Starting inside the shape counts as an immediate hit
+                if self.distance_to_point(origin).0 <= 0.0 {
+                    return Some((0.0, -direction));
+                }
+
+                let mut best: Option<f32> = None;
+                let mut consider = |t: f32| {
+                    if t >= 0.0 && best.map_or(true, |current| t < current) {
+                        best = Some(t);
+                    }
+                };
+
+                // Cylinder around the segment
+                let axis = b - a;
+                let axis_length_squared = axis.length_squared();
+                let offset = origin - a;
+                let axis_dot_direction = axis.dot(direction);
+                let quadratic_a = axis_length_squared - axis_dot_direction * axis_dot_direction;
+                if axis_length_squared > f32::EPSILON && quadratic_a > f32::EPSILON {
+                    let axis_dot_offset = axis.dot(offset);
+                    let quadratic_b = axis_length_squared * direction.dot(offset) - axis_dot_offset * axis_dot_direction;
+                    let quadratic_c = axis_length_squared * offset.length_squared()
+                        - axis_dot_offset * axis_dot_offset
+                        - radius * radius * axis_length_squared;
+                    let discriminant = quadratic_b * quadratic_b - quadratic_a * quadratic_c;
+                    if discriminant >= 0.0 {
+                        let t = (-quadratic_b - discriminant.sqrt()) / quadratic_a;
+                        let along_axis = axis_dot_offset + t * axis_dot_direction;
+                        if along_axis > 0.0 && along_axis < axis_length_squared {
+                            consider(t);
+                        }
+                    }
+                }
+
+                // Spherical caps
+                for cap in [a, b] {
+                    let to_origin = origin - cap;
+                    let half_b = direction.dot(to_origin);
+                    let discriminant = half_b * half_b - (to_origin.length_squared() - radius * radius);
+                    if discriminant >= 0.0 {
+                        consider(-half_b - discriminant.sqrt());
+                    }
+                }
+
+                best.map(|t| {
+                    let point = origin + direction * t;
+                    let normal = (point - closest_point_on_segment(a, b, point)).normalize_or_zero();
+                    (t, normal)
+                })
+            }
+            WorldShape::Box { center, axes, half_extents } => {
+                // Slab test in the box's local frame
+                let offset = origin - center;
+                let mut entry = f32::MIN;
+                let mut exit = f32::MAX;
+                let mut normal = -direction;
+                for i in 0..3 {
+                    let local_origin = offset.dot(axes[i]);
+                    let local_direction = direction.dot(axes[i]);
+                    if local_direction.abs() < f32::EPSILON {
+                        if local_origin.abs() > half_extents[i] {
+                            return None;
+                        }
+                        continue;
+                    }
+                    let near = (-half_extents[i].copysign(local_direction) - local_origin) / local_direction;
+                    let far = (half_extents[i].copysign(local_direction) - local_origin) / local_direction;
+                    if near > entry {
+                        entry = near;
+                        normal = -axes[i] * local_direction.signum();
+                    }
+                    exit = exit.min(far);
+                    if entry > exit || exit < 0.0 {
+                        return None;
+                    }
+                }
+                Some((entry.max(0.0), normal))
+            }
+        }
+    }
+
+    /// Travel distance of a sphere along a unit `direction` until it touches the shape.
+    fn sphere_cast_distance(&self, origin: Vec3, radius: f32, direction: Vec3, max_distance: f32) -> Option<(f32, Vec3)> {
+        match *self {
+            // Sweeping a sphere against a capsule is a ray against the capsule grown by the radius
+            WorldShape::Round { a, b, radius: shape_radius } => {
+                WorldShape::Round { a, b, radius: shape_radius + radius }.ray_distance(origin, direction)
+            }
+            // Boxes use conservative advancement: step by the current separation until touching
+            WorldShape::Box { .. } => {
+                let mut travelled = 0.0;
+                for _ in 0..64 {
+                    let center = origin + direction * travelled;
+                    let (distance, closest) = self.distance_to_point(center);
+                    let gap = distance - radius;
+                    if gap <= 1e-3 {
+                        let normal = (center - closest).normalize_or_zero();
+                        return Some((travelled, if normal == Vec3::ZERO { -direction } else { normal }));
+                    }
+                    travelled += gap;
+                    if travelled > max_distance {
+                        return None;
+                    }
+                }
+                None
+            }
+        }
+    }
+}
+
+/// Layer and entity filter for `SpatialQuery` lookups.
+#[derive(Clone)]
+pub struct SpatialQueryFilter {
+    /// Only colliders whose `collision_layer` is in this mask are considered.
+    pub mask: u32,
+    pub excluded_entities: Vec<Entity>,
+}
+
+impl Default for SpatialQueryFilter {
+    fn default() -> Self {
+        Self {
+            mask: u32::MAX,
+            excluded_entities: Vec::new(),
+        }
+    }
+}
+
+impl SpatialQueryFilter {
+    pub fn from_mask(mask: u32) -> Self {
+        Self { mask, ..default() }
+    }
+
+    pub fn with_excluded(mut self, entity: Entity) -> Self {
+        self.excluded_entities.push(entity);
+        self
+    }
+
+    fn accepts(&self, entity: Entity, collider: &Collider) -> bool {
+        self.mask & collider.collision_layer.mask() != 0 && !self.excluded_entities.contains(&entity)
+    }
+}
+
+/// Hit returned by ray and shape casts.
+#[derive(Clone, Copy, Debug)]
+pub struct CastHit {
+    pub entity: Entity,
+    /// Distance travelled along the cast direction before the hit.
+    pub distance: f32,
+    /// World-space point on the hit collider's surface.
+    pub point: Vec3,
+    /// Surface normal of the hit collider at `point`.
+    pub normal: Vec3,
+}
+
+/// Closest collider to a point, as returned by `SpatialQuery::nearest`.
+#[derive(Clone, Copy, Debug)]
+pub struct NearestHit {
+    pub entity: Entity,
+    pub distance: f32,
+    pub point: Vec3,
+}
+
+/// Raycasts, shape casts and overlap tests against every `Collider` in the world.
+#[derive(SystemParam)]
+pub struct SpatialQuery<'w, 's> {
+    colliders: Query<'w, 's, (Entity, &'static Transform, &'static Collider)>,
+}
+
+impl SpatialQuery<'_, '_> {
+    /// First collider hit by a ray, if any within `max_distance`.
+    pub fn cast_ray(&self, origin: Vec3, direction: Vec3, max_distance: f32, filter: &SpatialQueryFilter) -> Option<CastHit> {
+        let direction = direction.try_normalize()?;
+        self.closest_cast(origin, max_distance, 0.0, filter, |shape| shape.ray_distance(origin, direction))
+            .map(|(entity, distance, normal)| CastHit {
+                entity,
+                distance,
+                point: origin + direction * distance,
+                normal,
+            })
+    }
+
+    /// First collider touched by a sphere swept along `direction`, if any within `max_distance`.
+    pub fn cast_sphere(
+        &self,
+        origin: Vec3,
+        radius: f32,
+        direction: Vec3,
+        max_distance: f32,
+        filter: &SpatialQueryFilter,
+    ) -> Option<CastHit> {
+        let direction = direction.try_normalize()?;
+        self.closest_cast(origin, max_distance, radius, filter, |shape| {
+            shape.sphere_cast_distance(origin, radius, direction, max_distance)
+        })
+        .map(|(entity, distance, normal)| CastHit {
+            entity,
+            distance,
+            point: origin + direction * distance - normal * radius,
+            normal,
+        })
+    }
+
+    /// All colliders overlapping a sphere.
+    pub fn overlap_sphere(&self, center: Vec3, radius: f32, filter: &SpatialQueryFilter) -> Vec<Entity> {
+        self.colliders
+            .iter()
+            .filter(|(entity, transform, collider)| {
+                filter.accepts(*entity, collider)
+                    && transform.translation.distance(center) <= collider.bounding_radius() + radius
+                    && collider.shape.to_world(transform).distance_to_point(center).0 <= radius
+            })
+            .map(|(entity, _, _)| entity)
+            .collect()
+    }
+
+    /// Collider whose surface is closest to `point`, if any within `max_distance`.
+    pub fn nearest(&self, point: Vec3, max_distance: f32, filter: &SpatialQueryFilter) -> Option<NearestHit> {
+        let mut nearest: Option<NearestHit> = None;
+        for (entity, transform, collider) in self.colliders.iter() {
+            if !filter.accepts(entity, collider)
+                || transform.translation.distance(point) > collider.bounding_radius() + max_distance
+            {
+                continue;
+            }
+            let (distance, surface_point) = collider.shape.to_world(transform).distance_to_point(point);
+            if distance <= max_distance && nearest.map_or(true, |hit| distance < hit.distance) {
+                nearest = Some(NearestHit { entity, distance, point: surface_point });
+            }
+        }
+        nearest
+    }
+
+    fn closest_cast(
+        &self,
+        origin: Vec3,
+        max_distance: f32,
+        radius: f32,
+        filter: &SpatialQueryFilter,
+        cast: impl Fn(&WorldShape) -> Option<(f32, Vec3)>,
+    ) -> Option<(Entity, f32, Vec3)> {
+        let mut closest: Option<(Entity, f32, Vec3)> = None;
+        for (entity, transform, collider) in self.colliders.iter() {
+            if !filter.accepts(entity, collider)
+                || transform.translation.distance(origin) > collider.bounding_radius() + radius + max_distance
+            {
+                continue;
+            }
+            if let Some((distance, normal)) = cast(&collider.shape.to_world(transform)) {
+                if distance <= max_distance && closest.map_or(true, |(_, best, _)| distance < best) {
+                    closest = Some((entity, distance, normal));
+                }
+            }
+        }
+        closest
+    }
+}
+
+/// Uniform grid of collider bounds, rebuilt every physics step as the narrowphase broadphase.
+#[derive(Resource)]
+pub struct SpatialHash {
+    pub cell_size: f32,
+    pub cells: HashMap<IVec3, Vec<Entity>>,
+}
+
+impl Default for SpatialHash {
+    fn default() -> Self {
+        Self {
+            cell_size: 4.0,
+            cells: HashMap::new(),
+        }
+    }
+}
+
+impl SpatialHash {
+    pub fn cell_of(&self, point: Vec3) -> IVec3 {
+        (point / self.cell_size).floor().as_ivec3()
+    }
+
+    fn insert(&mut self, entity: Entity, min: Vec3, max: Vec3) {
+        let (min_cell, max_cell) = (self.cell_of(min), self.cell_of(max));
+        for x in min_cell.x..=max_cell.x {
+            for y in min_cell.y..=max_cell.y {
+                for z in min_cell.z..=max_cell.z {
+                    self.cells.entry(IVec3::new(x, y, z)).or_default().push(entity);
+                }
+            }
+        }
+    }
+}
+
+/// Trigger collider: reports collision events but is never pushed apart from anything.
+#[derive(Component)]
+pub struct Sensor;
+
+/// Sent on the first physics step two colliders touch.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct CollisionStarted {
+    pub entity_a: Entity,
+    pub entity_b: Entity,
+    pub point: Vec3,
+    /// Points from `entity_a` towards `entity_b`.
+    pub normal: Vec3,
+}
+
+/// Sent on every following physics step the two colliders keep touching.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct CollisionOngoing {
+    pub entity_a: Entity,
+    pub entity_b: Entity,
+    pub point: Vec3,
+    /// Points from `entity_a` towards `entity_b`.
+    pub normal: Vec3,
+}
+
+/// Sent once two colliders stop touching, including when one of them is despawned.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct CollisionEnded {
+    pub entity_a: Entity,
+    pub entity_b: Entity,
+}
+
+/// Contacts found by the last narrowphase pass, keyed by entity pair (lower entity first).
+#[derive(Resource, Default)]
+pub struct ActiveCollisions {
+    pub pairs: HashMap<(Entity, Entity), Contact>,
+}
+
+/// Orders an event's entity pair so the entity matching `is_first` comes first.
+pub fn order_pair(entity_a: Entity, entity_b: Entity, is_first: impl Fn(Entity) -> bool) -> Option<(Entity, Entity)> {
+    if is_first(entity_a) {
+        Some((entity_a, entity_b))
+    } else if is_first(entity_b) {
+        Some((entity_b, entity_a))
+    } else {
+        None
+    }
+}
+
 #[derive(Clone, Copy, PartialEq, Eq)]
 pub enum CollisionLayer {
     Default = 0,
@@ -88,6 +886,7 @@ pub enum CollisionLayer {
     Zombie = 2,
     Bullet = 3,
     Environment = 4,
+    Debris = 5,
 }
 
 impl CollisionLayer {
@@ -96,10 +895,88 @@ impl CollisionLayer {
     }
 }
 
+/// Poses at the last two fixed steps, used to render bodies smoothly between steps.
+#[derive(Component, Default)]
+pub struct PhysicsInterpolation {
+    pub previous: Vec3,
+    pub current: Vec3,
+    pub rendered: Vec3,
+    pub previous_rotation: Quat,
+    pub current_rotation: Quat,
+    pub rendered_rotation: Quat,
+    pub initialized: bool,
+}
+
+/// Environment collider that oscillates between `origin` and `origin + travel` and/or spins about Y,
+/// carrying any bodies standing on it.
+#[derive(Component)]
+pub struct MovingPlatform {
+    pub origin: Vec3,
+    pub travel: Vec3,
+    /// Seconds for a full back-and-forth trip; zero disables translation.
+    pub period: f32,
+    /// Yaw speed in radians per second.
+    pub angular_speed: f32,
+    pub elapsed: f32,
+    /// Linear velocity over the last physics step.
+    pub velocity: Vec3,
+    /// Where the platform was before the last physics step.
+    pub previous_translation: Vec3,
+    /// Rotation applied during the last physics step.
+    pub rotation_delta: Quat,
+}
+
+impl Default for MovingPlatform {
+    fn default() -> Self {
+        Self {
+            origin: Vec3::ZERO,
+            travel: Vec3::ZERO,
+            period: 0.0,
+            angular_speed: 0.0,
+            elapsed: 0.0,
+            velocity: Vec3::ZERO,
+            previous_translation: Vec3::ZERO,
+            rotation_delta: Quat::IDENTITY,
+        }
+    }
+}
+
+impl MovingPlatform {
+    /// Velocity of the platform surface at a world-space point.
+    pub fn velocity_at(&self, center: Vec3, point: Vec3) -> Vec3 {
+        self.velocity + Vec3::Y.cross(point - center) * self.angular_speed
+    }
+}
+
+/// Launches any grounded body standing on it straight up.
+#[derive(Component)]
+pub struct JumpPad {
+    pub launch_speed: f32,
+}
+
+impl Default for JumpPad {
+    fn default() -> Self {
+        Self { launch_speed: 20.0 }
+    }
+}
+
+/// Probes downwards for walkable environment colliders under the entity.
 #[derive(Component)]
 pub struct GroundDetector {
     pub is_grounded: bool,
+    /// Gap between the bottom of the collider and the ground below it.
     pub ground_distance: f32,
+    pub ground_normal: Vec3,
+    /// Environment entity currently being stood on.
+    pub ground_entity: Option<Entity>,
+    /// How far below the collider ground is still detected.
+    pub probe_distance: f32,
+    /// Steepest surface, in radians, that still counts as ground.
+    pub max_slope_angle: f32,
+    /// Tallest ledge that is climbed automatically while grounded, e.g. stairs.
+    pub step_height: f32,
+    /// Velocity of the surface being stood on, non-zero on moving platforms.
+    pub ground_velocity: Vec3,
 }
 
 impl Default for GroundDetector {
@@ -107,15 +984,33 @@ impl Default for GroundDetector {
         Self {
             is_grounded: false,
             ground_distance: 0.0,
+            ground_normal: Vec3::Y,
+            ground_entity: None,
+            probe_distance: 0.15,
+            max_slope_angle: 45.0_f32.to_radians(),
+            step_height: 0.4,
+            ground_velocity: Vec3::ZERO,
         }
     }
 }
 
+impl GroundDetector {
+    pub fn is_walkable(&self, normal: Vec3) -> bool {
+        normal.y >= self.max_slope_angle.cos()
+    }
+}
+
 #[derive(Component)]
 pub struct Jumper {
     pub can_jump: bool,
     pub jump_count: u32,
     pub max_jumps: u32, // For multi-jump
+    /// Seconds after walking off a ledge during which the ground jump is still available.
+    pub coyote_time: f32,
+    /// Seconds a jump pressed too early is remembered, so it fires on landing.
+    pub jump_buffer_time: f32,
+    pub time_since_grounded: f32,
+    pub buffered_jump: f32,
 }
 
 impl Default for Jumper {
@@ -124,6 +1019,10 @@ impl Default for Jumper {
             can_jump: true,
             jump_count: 0,
             max_jumps: 1, // Single jump by default
+            coyote_time: 0.12,
+            jump_buffer_time: 0.15,
+            time_since_grounded: 0.0,
+            buffered_jump: 0.0,
         }
     }
 }
@@ -143,8 +1042,122 @@ impl Default for CollisionDamage {
     }
 }
 
+/// Converts a per-reference-step retention factor (e.g. `RigidBody::drag`) into
+/// the factor for a step of `dt` seconds, so damping is independent of frame rate.
+pub fn damping_factor(retention: f32, dt: f32) -> f32 {
+    retention.powf(dt * REFERENCE_HZ)
+}
+
 // Physics Systems
 
+fn restore_physics_transforms(
+    mut commands: Commands,
+    mut query: Query<(&mut Transform, &mut PhysicsInterpolation)>,
+    new_bodies: Query<Entity, (Or<(With<RigidBody>, With<MovingPlatform>)>, Without<PhysicsInterpolation>)>,
+) {
+    for entity in new_bodies.iter() {
+        commands.entity(entity).insert(PhysicsInterpolation::default());
+    }
+
+    for (mut transform, mut interpolation) in query.iter_mut() {
+        // A pose that differs from what we rendered was set by gameplay code (e.g. a teleport)
+        if !interpolation.initialized || transform.translation != interpolation.rendered {
+            interpolation.current = transform.translation;
+        }
+        if !interpolation.initialized || transform.rotation != interpolation.rendered_rotation {
+            interpolation.current_rotation = transform.rotation;
+        }
+        interpolation.initialized = true;
+
+        interpolation.previous = interpolation.current;
+        interpolation.previous_rotation = interpolation.current_rotation;
+        transform.translation = interpolation.current;
+        transform.rotation = interpolation.current_rotation;
+    }
+}
+
+fn record_physics_transforms(
+    mut query: Query<(&Transform, &mut PhysicsInterpolation)>,
+) {
+    for (transform, mut interpolation) in query.iter_mut() {
+        interpolation.current = transform.translation;
+        interpolation.rendered = transform.translation;
+        interpolation.current_rotation = transform.rotation;
+        interpolation.rendered_rotation = transform.rotation;
+    }
+}
+
+fn interpolate_transforms(
+    mut query: Query<(&mut Transform, &mut PhysicsInterpolation)>,
+    fixed_time: Res<Time<Fixed>>,
+) {
+    let alpha = fixed_time.overstep_fraction();
+
+    for (mut transform, mut interpolation) in query.iter_mut() {
+        if !interpolation.initialized {
+            continue;
+        }
+
+        transform.translation = interpolation.previous.lerp(interpolation.current, alpha);
+        transform.rotation = interpolation.previous_rotation.slerp(interpolation.current_rotation, alpha);
+        interpolation.rendered = transform.translation;
+        interpolation.rendered_rotation = transform.rotation;
+    }
+}
+
+fn move_platforms(
+    mut platform_query: Query<(&mut Transform, &mut MovingPlatform)>,
+    time: Res<Time>,
+) {
+    let dt = time.delta_seconds();
+
+    for (mut transform, mut platform) in platform_query.iter_mut() {
+        platform.elapsed += dt;
+        platform.previous_translation = transform.translation;
+
+        if platform.period > 0.0 {
+            let cycle = platform.elapsed / platform.period * std::f32::consts::TAU;
+            let target = platform.origin + platform.travel * (0.5 - 0.5 * cycle.cos());
+            if dt > 0.0 {
+                platform.velocity = (target - transform.translation) / dt;
+            }
+            transform.translation = target;
+        }
+
+        platform.rotation_delta = Quat::from_rotation_y(platform.angular_speed * dt);
+        transform.rotation = platform.rotation_delta * transform.rotation;
+    }
+}
+
+fn carry_platform_riders(
+    mut rider_query: Query<(&mut Transform, &GroundDetector), Without<MovingPlatform>>,
+    platform_query: Query<(&Transform, &MovingPlatform)>,
+) {
+    for (mut transform, ground_detector) in rider_query.iter_mut() {
+        let Some((platform_transform, platform)) = ground_detector.ground_entity.and_then(|entity| platform_query.get(entity).ok()) else {
+            continue;
+        };
+
+        let offset = transform.translation - platform.previous_translation;
+        transform.translation = platform_transform.translation + platform.rotation_delta * offset;
+    }
+}
+
+fn apply_jump_pads(
+    mut body_query: Query<(&mut RigidBody, &GroundDetector)>,
+    jump_pad_query: Query<&JumpPad>,
+) {
+    for (mut rigidbody, ground_detector) in body_query.iter_mut() {
+        let Some(jump_pad) = ground_detector.ground_entity.and_then(|entity| jump_pad_query.get(entity).ok()) else {
+            continue;
+        };
+
+        if !rigidbody.is_kinematic {
+            rigidbody.velocity.y = jump_pad.launch_speed;
+        }
+    }
+}
+
 fn apply_gravity(
     mut query: Query<(&mut RigidBody, &GroundDetector), Without<crate::Player>>,
     mut player_query: Query<(&mut RigidBody, &GroundDetector), With<crate::Player>>,
@@ -176,7 +1189,19 @@ fn apply_physics_movement(
     let dt = time.delta_seconds();
     
     for (mut transform, mut rigidbody) in query.iter_mut() {
-        if !rigidbody.is_kinematic {
+        if rigidbody.is_kinematic {
+            if let Some(target) = rigidbody.kinematic_target.take() {
+                if dt > 0.0 {
+                    rigidbody.velocity = (target - transform.translation) / dt;
+                }
+                transform.translation = target;
+            }
+        } else {
+            // Integrate forces accumulated since the last step
+            let acceleration = rigidbody.accumulated_force * rigidbody.inverse_mass();
+            rigidbody.velocity += acceleration * dt;
+            rigidbody.accumulated_force = Vec3::ZERO;
+
             // Store drag value before using mutable borrow
             let drag = rigidbody.drag;
             
@@ -184,7 +1209,7 @@ fn apply_physics_movement(
             transform.translation += rigidbody.velocity * dt;
             
             // Apply drag
-            rigidbody.velocity *= drag;
+            rigidbody.velocity *= damping_factor(drag, dt);
             
             // Zero out very small velocities to prevent jitter
             if rigidbody.velocity.length() < 0.01 {
@@ -196,77 +1221,193 @@ fn apply_physics_movement(
 
 fn handle_jumping(
     mut player_query: Query<(&mut RigidBody, &mut Jumper, &GroundDetector), With<Player>>,
-    keyboard_input: Res<ButtonInput<KeyCode>>,
+    action_state: Res<ActionState<PlayerAction>>,
     settings: Res<PhysicsSettings>,
+    time: Res<Time>,
 ) {
     if let Ok((mut rigidbody, mut jumper, ground_detector)) = player_query.get_single_mut() {
-        if keyboard_input.just_pressed(KeyCode::Space) {
-            // Reset jump count when grounded
-            if ground_detector.is_grounded {
-                jumper.jump_count = 0;
-            }
-            
-            // Check if we can jump
-            if jumper.can_jump && jumper.jump_count < jumper.max_jumps {
-                rigidbody.velocity.y = settings.jump_force;
-                jumper.jump_count += 1;
-                jumper.can_jump = false; // Prevent multiple jumps on single press
-            }
+        let dt = time.delta_seconds();
+
+        // Still rising from a jump until the next physics step clears the ground flag
+        if ground_detector.is_grounded && rigidbody.velocity.y <= 0.0 {
+            jumper.time_since_grounded = 0.0;
+            jumper.jump_count = 0;
+        } else {
+            jumper.time_since_grounded += dt;
+        }
+
+        // Walking off a ledge spends the ground jump once coyote time runs out
+        let on_ground = jumper.time_since_grounded <= jumper.coyote_time && jumper.jump_count == 0;
+        if !on_ground && jumper.jump_count == 0 {
+            jumper.jump_count = 1;
+        }
+
+        // Remember presses briefly so a jump just before landing still happens
+        if action_state.just_pressed(&PlayerAction::Jump) {
+            jumper.buffered_jump = jumper.jump_buffer_time;
+        } else {
+            jumper.buffered_jump = (jumper.buffered_jump - dt).max(0.0);
+        }
+
+        if jumper.buffered_jump > 0.0 && jumper.can_jump && jumper.jump_count < jumper.max_jumps {
+            rigidbody.velocity.y = settings.jump_force;
+            jumper.jump_count += 1;
+            jumper.buffered_jump = 0.0;
+            jumper.can_jump = false; // Prevent multiple jumps on single press
         }
         
-        if keyboard_input.just_released(KeyCode::Space) {
+        if !action_state.pressed(&PlayerAction::Jump) {
             jumper.can_jump = true;
         }
     }
 }
 
+/// Probe shapes start slightly inside the collider so resting contacts are still found.
+const GROUND_PROBE_LIFT: f32 = 0.05;
+
 fn apply_ground_detection(
-    mut query: Query<(&Transform, &mut GroundDetector, &Collider, &mut Jumper)>,
-    settings: Res<PhysicsSettings>,
+    mut query: Query<(Entity, &Transform, &Collider, &mut GroundDetector, Option<&mut RigidBody>)>,
+    platform_query: Query<(&Transform, &MovingPlatform)>,
+    spatial_query: SpatialQuery,
 ) {
-    for (transform, mut ground_detector, collider, mut jumper) in query.iter_mut() {
-        let ground_check_distance = collider.radius + 0.1;
-        let distance_to_ground = transform.translation.y - settings.ground_level;
-        
-        ground_detector.ground_distance = distance_to_ground;
-        ground_detector.is_grounded = distance_to_ground <= ground_check_distance;
-        
-        // Reset jump count when grounded
-        if ground_detector.is_grounded {
-            jumper.jump_count = 0;
+    for (entity, transform, collider, mut ground_detector, rigidbody) in query.iter_mut() {
+        let previous_ground_velocity = ground_detector.ground_velocity;
+
+        // Sweep a sphere slightly narrower than the collider's base so walls beside it aren't ground
+        let extents = collider.half_extents(transform.rotation);
+        let probe_radius = extents.x.min(extents.z) * 0.9;
+        let probe_origin = transform.translation - Vec3::Y * (extents.y - probe_radius - GROUND_PROBE_LIFT);
+        let filter = SpatialQueryFilter::from_mask(CollisionLayer::Environment.mask()).with_excluded(entity);
+
+        let hit = spatial_query.cast_sphere(
+            probe_origin,
+            probe_radius,
+            Vec3::NEG_Y,
+            GROUND_PROBE_LIFT + ground_detector.probe_distance,
+            &filter,
+        );
+
+        match hit {
+            Some(hit) if ground_detector.is_walkable(hit.normal) => {
+                ground_detector.is_grounded = true;
+                ground_detector.ground_distance = hit.distance - GROUND_PROBE_LIFT;
+                ground_detector.ground_normal = hit.normal;
+                ground_detector.ground_entity = Some(hit.entity);
+                ground_detector.ground_velocity = platform_query
+                    .get(hit.entity)
+                    .map_or(Vec3::ZERO, |(platform_transform, platform)| {
+                        platform.velocity_at(platform_transform.translation, hit.point)
+                    });
+            }
+            _ => {
+                ground_detector.is_grounded = false;
+                ground_detector.ground_distance = hit.map_or(f32::INFINITY, |hit| hit.distance - GROUND_PROBE_LIFT);
+                ground_detector.ground_normal = hit.map_or(Vec3::Y, |hit| hit.normal);
+                ground_detector.ground_entity = None;
+                ground_detector.ground_velocity = Vec3::ZERO;
+
+                // Keep the platform's momentum when stepping or jumping off it
+                if let Some(mut rigidbody) = rigidbody {
+                    rigidbody.velocity += previous_ground_velocity;
+                }
+            }
+        }
+    }
+}
+
+/// Keeps grounded bodies on the surface when walking down slopes and steps.
+fn snap_to_ground(
+    mut query: Query<(&mut Transform, &mut RigidBody, &GroundDetector)>,
+) {
+    for (mut transform, mut rigidbody, ground_detector) in query.iter_mut() {
+        if rigidbody.is_kinematic || !ground_detector.is_grounded || rigidbody.velocity.y > 0.0 {
+            continue;
         }
+
+        if ground_detector.ground_distance > 0.0 {
+            transform.translation.y -= ground_detector.ground_distance;
+        }
+        rigidbody.velocity.y = 0.0;
     }
 }
 
 fn player_zombie_collision(
-    mut player_query: Query<(&Transform, &Collider, &mut Player), (With<Player>, Without<Zombie>)>,
-    zombie_query: Query<(&Transform, &Collider, &CollisionDamage), (With<Zombie>, Without<Player>)>,
+    mut started_events: EventReader<CollisionStarted>,
+    mut ongoing_events: EventReader<CollisionOngoing>,
+    mut damage_events: EventWriter<PlayerDamageEvent>,
+    player_query: Query<(), (With<Player>, Without<Zombie>)>,
+    mut zombie_query: Query<(&Transform, &mut CollisionDamage), (With<Zombie>, Without<Player>)>,
     settings: Res<PhysicsSettings>,
     time: Res<Time>,
 ) {
-    if let Ok((player_transform, player_collider, mut player)) = player_query.get_single_mut() {
-        let current_time = time.elapsed_seconds();
-        
-        for (zombie_transform, zombie_collider, collision_damage) in zombie_query.iter() {
-            let distance = player_transform.translation.distance(zombie_transform.translation);
-            let collision_distance = player_collider.radius + zombie_collider.radius;
-            
-            if distance <= collision_distance {
-                // Check damage cooldown to prevent rapid damage
-                if current_time - collision_damage.last_damage_time > settings.player_damage_cooldown {
-                    player.health -= collision_damage.damage;
-                    println!("Player takes {} damage! Health: {}", collision_damage.damage, player.health);
-                    
-                    // Update damage time (we'd need to make collision_damage mutable for this)
-                    // For now, we'll implement a different approach using a resource or event
-                }
-            }
+    let current_time = time.elapsed_seconds();
+    let touching = started_events.read().map(|event| (event.entity_a, event.entity_b))
+        .chain(ongoing_events.read().map(|event| (event.entity_a, event.entity_b)));
+
+    for (entity_a, entity_b) in touching {
+        let Some((_, zombie_entity)) = order_pair(entity_a, entity_b, |entity| player_query.contains(entity)) else {
+            continue;
+        };
+        let Ok((zombie_transform, mut collision_damage)) = zombie_query.get_mut(zombie_entity) else {
+            continue;
+        };
+
+        // Check damage cooldown to prevent rapid damage
+        if current_time - collision_damage.last_damage_time > settings.player_damage_cooldown {
+            collision_damage.last_damage_time = current_time;
+            damage_events.send(PlayerDamageEvent {
+                amount: collision_damage.damage,
+                source: zombie_transform.translation,
+            });
+        }
+    }
+}
+
+fn player_zombie_separation(
+    mut player_query: Query<(&mut Transform, &mut RigidBody, &Collider), (With<Player>, Without<Zombie>, Without<Sensor>)>,
+    mut zombie_query: Query<(&mut Transform, &mut RigidBody, &Collider), (With<Zombie>, Without<Player>, Without<Sensor>)>,
+    settings: Res<PhysicsSettings>,
+) {
+    let Ok((mut player_transform, mut player_body, player_collider)) = player_query.get_single_mut() else {
+        return;
+    };
+
+    for (mut zombie_transform, mut zombie_body, zombie_collider) in zombie_query.iter_mut() {
+        if !player_collider.interacts_with(zombie_collider) {
+            continue;
+        }
+
+        let Some(contact) = compute_contact(player_collider, &player_transform, zombie_collider, &zombie_transform) else {
+            continue;
+        };
+
+        // Keep separation horizontal so zombies can't climb onto or bury the player
+        let normal = Vec3::new(contact.normal.x, 0.0, contact.normal.z).normalize_or_zero();
+        if normal == Vec3::ZERO {
+            continue;
+        }
+
+        // Heavier bodies give way less; the zombie takes whatever the player doesn't so the overlap is always resolved
+        let total_mass = player_body.mass + zombie_body.mass;
+        let player_share = zombie_body.mass / total_mass * settings.player_pushback;
+        let zombie_share = 1.0 - player_share;
+        let correction = contact.penetration * settings.body_separation;
+
+        player_transform.translation -= normal * correction * player_share;
+        zombie_transform.translation += normal * correction * zombie_share;
+
+        // Cancel the velocity driving the two bodies into each other
+        let closing_speed = (player_body.velocity - zombie_body.velocity).dot(normal);
+        if closing_speed > 0.0 {
+            let impulse = normal * closing_speed * settings.collision_damping;
+            player_body.velocity -= impulse * player_share;
+            zombie_body.velocity += impulse * zombie_share;
         }
     }
 }
 
 fn zombie_zombie_collision(
-    mut zombie_query: Query<(&mut Transform, &mut RigidBody, &Collider), With<Zombie>>,
+    mut zombie_query: Query<(&mut Transform, &mut RigidBody, &Collider), (With<Zombie>, Without<Sensor>)>,
     settings: Res<PhysicsSettings>,
 ) {
     let mut combinations = zombie_query.iter_combinations_mut();
@@ -275,16 +1416,16 @@ fn zombie_zombie_collision(
         (mut transform_a, mut rigidbody_a, collider_a),
         (mut transform_b, mut rigidbody_b, collider_b)
     ]) = combinations.fetch_next() {
-        let distance = transform_a.translation.distance(transform_b.translation);
-        let collision_distance = collider_a.radius + collider_b.radius;
-        
-        if distance <= collision_distance && distance > 0.0 {
-            // Calculate separation vector
-            let separation = (transform_a.translation - transform_b.translation).normalize();
-            let overlap = collision_distance - distance;
+        if !collider_a.interacts_with(collider_b) {
+            continue;
+        }
+
+        if let Some(contact) = compute_contact(collider_a, &transform_a, collider_b, &transform_b) {
+            // Separation vector points from b towards a
+            let separation = -contact.normal;
             
             // Apply separation to prevent stacking
-            let separation_force = separation * overlap * 0.5;
+            let separation_force = separation * contact.penetration * 0.5;
             
             // Move zombies apart based on their masses
             let total_mass = rigidbody_a.mass + rigidbody_b.mass;
@@ -308,66 +1449,173 @@ fn zombie_zombie_collision(
     }
 }
 
-fn check_arena_bounds(
-    mut query: Query<(&mut Transform, &mut RigidBody, &Collider)>,
+fn resolve_environment_collisions(
+    mut body_query: Query<(&mut Transform, &mut RigidBody, &Collider, Option<&GroundDetector>), Without<Sensor>>,
+    static_query: Query<(&Transform, &Collider), (Without<RigidBody>, Without<Sensor>)>,
 ) {
-    let arena_size = 24.0;
-    
-    for (mut transform, mut rigidbody, collider) in query.iter_mut() {
-        let effective_radius = collider.radius;
-        
-        // X bounds
-        if transform.translation.x + effective_radius > arena_size {
-            transform.translation.x = arena_size - effective_radius;
-            rigidbody.velocity.x = -rigidbody.velocity.x * rigidbody.restitution;
-        } else if transform.translation.x - effective_radius < -arena_size {
-            transform.translation.x = -arena_size + effective_radius;
-            rigidbody.velocity.x = -rigidbody.velocity.x * rigidbody.restitution;
-        }
-        
-        // Z bounds
-        if transform.translation.z + effective_radius > arena_size {
-            transform.translation.z = arena_size - effective_radius;
-            rigidbody.velocity.z = -rigidbody.velocity.z * rigidbody.restitution;
-        } else if transform.translation.z - effective_radius < -arena_size {
-            transform.translation.z = -arena_size + effective_radius;
-            rigidbody.velocity.z = -rigidbody.velocity.z * rigidbody.restitution;
-        }
-        
-        // Ground bounds
-        if transform.translation.y - effective_radius < 0.0 {
-            transform.translation.y = effective_radius;
-            if rigidbody.velocity.y < 0.0 {
-                rigidbody.velocity.y = -rigidbody.velocity.y * rigidbody.restitution;
+    for (mut transform, mut rigidbody, collider, ground_detector) in body_query.iter_mut() {
+        for (static_transform, static_collider) in static_query.iter() {
+            if static_collider.collision_layer != CollisionLayer::Environment || !collider.interacts_with(static_collider) {
+                continue;
+            }
+
+            if let Some(contact) = compute_contact(collider, &transform, static_collider, static_transform) {
+                // Grounded bodies walking into a low ledge climb onto it instead of stopping
+                if let Some(ground_detector) = ground_detector {
+                    if ground_detector.is_grounded && !ground_detector.is_walkable(-contact.normal) {
+                        if let Some(lift) = step_up_height(collider, &transform, static_collider, static_transform, ground_detector.step_height) {
+                            transform.translation.y += lift;
+                            continue;
+                        }
+                    }
+                }
+
+                // Push the body out along the contact normal
+                transform.translation -= contact.normal * contact.penetration;
+
+                // Bounce off the surface, removing the velocity going into it
+                let into_surface = rigidbody.velocity.dot(contact.normal);
+                if into_surface > 0.0 {
+                    let restitution = rigidbody.restitution;
+                    rigidbody.velocity -= contact.normal * into_surface * (1.0 + restitution);
+                }
             }
         }
     }
 }
 
+/// Smallest upward offset, up to `max_height`, that frees `collider` from `obstacle`.
+fn step_up_height(
+    collider: &Collider,
+    transform: &Transform,
+    obstacle: &Collider,
+    obstacle_transform: &Transform,
+    max_height: f32,
+) -> Option<f32> {
+    let lifted = |height: f32| Transform {
+        translation: transform.translation + Vec3::Y * height,
+        ..*transform
+    };
+    if max_height <= 0.0 || compute_contact(collider, &lifted(max_height), obstacle, obstacle_transform).is_some() {
+        return None;
+    }
+
+    let (mut low, mut high) = (0.0, max_height);
+    for _ in 0..8 {
+        let middle = (low + high) * 0.5;
+        if compute_contact(collider, &lifted(middle), obstacle, obstacle_transform).is_some() {
+            low = middle;
+        } else {
+            high = middle;
+        }
+    }
+    Some(high)
+}
+
 fn apply_friction(
     mut query: Query<(&mut RigidBody, &GroundDetector)>,
     time: Res<Time>,
 ) {
+    let dt = time.delta_seconds();
+
     for (mut rigidbody, ground_detector) in query.iter_mut() {
         if ground_detector.is_grounded {
-            // Apply ground friction
-            let friction_force = rigidbody.friction * time.delta_seconds();
-            rigidbody.velocity.x *= 1.0 - friction_force;
-            rigidbody.velocity.z *= 1.0 - friction_force;
+            // Apply ground friction as exponential decay so it doesn't depend on step size
+            let friction_factor = (-rigidbody.friction * dt).exp();
+            rigidbody.velocity.x *= friction_factor;
+            rigidbody.velocity.z *= friction_factor;
         }
     }
 }
 
-fn update_collision_events(
-    mut collision_damage_query: Query<&mut CollisionDamage>,
-    time: Res<Time>,
+pub fn update_spatial_hash(
+    collider_query: Query<(Entity, &Transform, &Collider)>,
+    mut spatial_hash: ResMut<SpatialHash>,
 ) {
-    let current_time = time.elapsed_seconds();
-    for mut collision_damage in collision_damage_query.iter_mut() {
-        // This system would typically handle collision event updates
-        // For now, we just ensure the damage time is properly tracked
-        if collision_damage.last_damage_time == 0.0 {
-            collision_damage.last_damage_time = current_time;
+    spatial_hash.cells.clear();
+    for (entity, transform, collider) in collider_query.iter() {
+        let extents = collider.half_extents(transform.rotation) + CONTACT_SKIN;
+        spatial_hash.insert(entity, transform.translation - extents, transform.translation + extents);
+    }
+}
+
+fn detect_collisions(
+    collider_query: Query<(&Transform, &Collider, Has<RigidBody>, Has<Sensor>)>,
+    spatial_hash: Res<SpatialHash>,
+    mut active_collisions: ResMut<ActiveCollisions>,
+    mut started_events: EventWriter<CollisionStarted>,
+    mut ongoing_events: EventWriter<CollisionOngoing>,
+    mut ended_events: EventWriter<CollisionEnded>,
+) {
+    let mut candidates = HashSet::new();
+    for entities in spatial_hash.cells.values() {
+        for (i, &entity_a) in entities.iter().enumerate() {
+            for &entity_b in &entities[i + 1..] {
+                candidates.insert(if entity_a < entity_b { (entity_a, entity_b) } else { (entity_b, entity_a) });
+            }
+        }
+    }
+
+    let mut pairs = HashMap::new();
+    for (entity_a, entity_b) in candidates {
+        let (Ok((transform_a, collider_a, body_a, sensor_a)), Ok((transform_b, collider_b, body_b, sensor_b))) =
+            (collider_query.get(entity_a), collider_query.get(entity_b)) else {
+            continue;
+        };
+
+        // Static geometry never reports collisions with itself
+        if !body_a && !body_b && !sensor_a && !sensor_b {
+            continue;
+        }
+        if !collider_a.interacts_with(collider_b) {
+            continue;
+        }
+
+        let max_distance = collider_a.bounding_radius() + collider_b.bounding_radius() + CONTACT_SKIN;
+        if transform_a.translation.distance_squared(transform_b.translation) > max_distance * max_distance {
+            continue;
+        }
+
+        let inflated = collider_a.shape.inflated(CONTACT_SKIN);
+        if let Some(mut contact) = contact_between(inflated, transform_a, collider_b.shape, transform_b) {
+            contact.penetration -= CONTACT_SKIN;
+            pairs.insert((entity_a, entity_b), contact);
+        }
+    }
+
+    for (&(entity_a, entity_b), contact) in pairs.iter() {
+        if active_collisions.pairs.contains_key(&(entity_a, entity_b)) {
+            ongoing_events.send(CollisionOngoing { entity_a, entity_b, point: contact.point, normal: contact.normal });
+        } else {
+            started_events.send(CollisionStarted { entity_a, entity_b, point: contact.point, normal: contact.normal });
+        }
+    }
+
+    for &(entity_a, entity_b) in active_collisions.pairs.keys() {
+        if !pairs.contains_key(&(entity_a, entity_b)) {
+            ended_events.send(CollisionEnded { entity_a, entity_b });
         }
     }
-} 
\ No newline at end of file
+
+    active_collisions.pairs = pairs;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn damping_factor_matches_retention_at_reference_step() {
+        let retention = 0.9;
+        assert!((damping_factor(retention, 1.0 / REFERENCE_HZ) - retention).abs() < 1e-6);
+    }
+
+    #[test]
+    fn damping_factor_is_independent_of_step_size() {
+        let retention = 0.8;
+        let one_step = damping_factor(retention, 1.0 / 30.0);
+        let two_steps = damping_factor(retention, 1.0 / 60.0).powi(2);
+        assert!((one_step - two_steps).abs() < 1e-6);
+        assert_eq!(damping_factor(retention, 0.0), 1.0);
+    }
+}
@@ -1,22 +1,30 @@
 use bevy::prelude::*;
-use crate::{GameCamera, GameConfig, GameState, physics::*};
+use leafwing_input_manager::prelude::*;
+use crate::{GameCamera, GameConfig, GameState, InRun, camera::camera_follows_player, controls::PlayerAction, gamepad::*, physics::*, weapons::WeaponKind};
 
 pub struct PlayerPlugin;
 
 impl Plugin for PlayerPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(GameState::InGame), setup_player)
+        app.init_resource::<MovementSettings>()
+            .init_resource::<DamageSettings>()
+            .init_resource::<StaminaSettings>()
+            .add_event::<PlayerDamageEvent>()
+            .add_event::<PlayerHitEvent>()
+            .add_systems(OnEnter(InRun), (reset_player_on_restart, setup_player).chain())
+            .add_systems(FixedUpdate, update_crouch.run_if(in_state(GameState::InGame)))
             .add_systems(
                 Update,
                 (
-                    player_movement,
+                    player_movement.run_if(camera_follows_player),
+                    (apply_player_damage, regenerate_player_health).chain(),
+                    regenerate_stamina.after(player_movement),
                     handle_shooting,
                     update_player_effects,
-                    sync_camera_to_player,
+                    sync_camera_to_player.run_if(camera_follows_player),
                 ).run_if(in_state(GameState::InGame)),
             )
-            .add_systems(OnExit(GameState::InGame), cleanup_player)
-            .add_systems(OnEnter(GameState::InGame), reset_player_on_restart);
+            .add_systems(OnExit(InRun), cleanup_player);
     }
 }
 
@@ -26,12 +34,55 @@ pub struct Player {
     pub max_health: f32,
     pub speed: f32,
     pub is_shooting: bool,
+    /// Set on frames where a shot goes off; bullets are spawned from it.
+    pub shot_fired: bool,
     pub last_shot: f32,
+    pub weapon: WeaponKind,
     pub psychedelic_charge: f32,
     pub kill_count: u32,
     pub score: f32,
     pub acceleration: f32,
     pub air_control: f32,
+    pub armor: f32,
+    pub max_armor: f32,
+    pub time_since_damage: f32,
+    /// Remaining seconds during which hits are ignored.
+    pub invulnerability_timer: f32,
+    pub stamina: f32,
+    pub max_stamina: f32,
+    pub time_since_stamina_use: f32,
+    /// Set when stamina runs out; sprinting, dashing and melee are locked until it recovers.
+    pub is_exhausted: bool,
+    /// Game time of the last melee swing.
+    pub last_melee: f32,
+    /// Scales bullet and melee damage; set by power-ups.
+    pub damage_multiplier: f32,
+    /// Scales movement speed; set by power-ups.
+    pub speed_multiplier: f32,
+    pub is_invincible: bool,
+    /// Fraction of damage dealt returned as health; set by perks.
+    pub lifesteal: f32,
+}
+
+impl Player {
+    /// Whether an action costing `amount` stamina can be started.
+    pub fn has_stamina(&self, amount: f32) -> bool {
+        !self.is_exhausted && self.stamina >= amount
+    }
+
+    pub fn spend_stamina(&mut self, amount: f32) {
+        self.stamina = (self.stamina - amount).max(0.0);
+        self.time_since_stamina_use = 0.0;
+        if self.stamina <= 0.0 {
+            self.is_exhausted = true;
+        }
+    }
+
+    /// Scores a bullet or melee hit that dealt `damage`, healing by the lifesteal share of it.
+    pub fn reward_hit(&mut self, damage: f32) {
+        self.score += 10.0;
+        self.health = (self.health + damage * self.lifesteal).min(self.max_health);
+    }
 }
 
 impl Default for Player {
@@ -41,12 +92,27 @@ impl Default for Player {
             max_health: 100.0,
             speed: 5.0,
             is_shooting: false,
+            shot_fired: false,
             last_shot: 0.0,
+            weapon: WeaponKind::default(),
             psychedelic_charge: 0.0,
             kill_count: 0,
             score: 0.0,
             acceleration: 20.0,
             air_control: 0.3,
+            armor: 50.0,
+            max_armor: 100.0,
+            time_since_damage: 0.0,
+            invulnerability_timer: 0.0,
+            stamina: 100.0,
+            max_stamina: 100.0,
+            time_since_stamina_use: 0.0,
+            is_exhausted: false,
+            last_melee: 0.0,
+            damage_multiplier: 1.0,
+            speed_multiplier: 1.0,
+            is_invincible: false,
+            lifesteal: 0.0,
         }
     }
 }
@@ -54,10 +120,157 @@ impl Default for Player {
 #[derive(Component)]
 pub struct PlayerBody;
 
-fn setup_player(mut commands: Commands) {
+#[derive(Resource)]
+pub struct MovementSettings {
+    pub sprint_multiplier: f32,
+    pub crouch_multiplier: f32,
+    pub standing_half_height: f32,
+    pub crouching_half_height: f32,
+    /// Camera height above the capsule center.
+    pub standing_eye_height: f32,
+    pub crouching_eye_height: f32,
+    /// How quickly the camera moves between standing and crouching height.
+    pub eye_height_speed: f32,
+    /// Fraction of walking speed needed to turn a sprinting crouch into a slide.
+    pub slide_min_speed_factor: f32,
+    pub slide_boost: f32,
+    pub slide_duration: f32,
+    /// Per-step velocity retention while sliding, in place of the player's normal drag.
+    pub slide_drag: f32,
+    pub dash_speed: f32,
+    pub dash_duration: f32,
+    pub dash_cooldown: f32,
+}
+
+impl Default for MovementSettings {
+    fn default() -> Self {
+        Self {
+            sprint_multiplier: 1.5,
+            crouch_multiplier: 0.5,
+            standing_half_height: 0.4,
+            crouching_half_height: 0.0,
+            standing_eye_height: 1.4,
+            crouching_eye_height: 0.9,
+            eye_height_speed: 12.0,
+            slide_min_speed_factor: 1.2,
+            slide_boost: 4.0,
+            slide_duration: 0.9,
+            slide_drag: 0.995,
+            dash_speed: 18.0,
+            dash_duration: 0.15,
+            dash_cooldown: 1.0,
+        }
+    }
+}
+
+#[derive(Resource)]
+pub struct DamageSettings {
+    /// Fraction of each hit absorbed by armor while any is left.
+    pub armor_absorption: f32,
+    pub invulnerability_duration: f32,
+    /// Seconds without taking damage before health and armor start regenerating.
+    pub regen_delay: f32,
+    pub regen_rate: f32,
+    /// Slower than health, so armor lost in a fight stays lost for a while.
+    pub armor_regen_rate: f32,
+}
+
+impl Default for DamageSettings {
+    fn default() -> Self {
+        Self {
+            armor_absorption: 0.6,
+            invulnerability_duration: 0.5,
+            regen_delay: 4.0,
+            regen_rate: 8.0,
+            armor_regen_rate: 2.0,
+        }
+    }
+}
+
+#[derive(Resource)]
+pub struct StaminaSettings {
+    /// Stamina per second while sprinting.
+    pub sprint_drain: f32,
+    pub dash_cost: f32,
+    pub melee_cost: f32,
+    /// Seconds after the last use before stamina starts coming back.
+    pub regen_delay: f32,
+    pub regen_rate: f32,
+    /// Stamina needed to shake off exhaustion.
+    pub exhaustion_recovery: f32,
+    pub exhausted_speed_multiplier: f32,
+}
+
+impl Default for StaminaSettings {
+    fn default() -> Self {
+        Self {
+            sprint_drain: 20.0,
+            dash_cost: 25.0,
+            melee_cost: 15.0,
+            regen_delay: 1.0,
+            regen_rate: 30.0,
+            exhaustion_recovery: 40.0,
+            exhausted_speed_multiplier: 0.6,
+        }
+    }
+}
+
+/// Damage dealt to the player before armor and invulnerability are taken into account.
+#[derive(Event)]
+pub struct PlayerDamageEvent {
+    pub amount: f32,
+    /// World position of whatever dealt the damage.
+    pub source: Vec3,
+}
+
+/// A hit that actually landed, for HUD feedback.
+#[derive(Event)]
+pub struct PlayerHitEvent {
+    pub health_damage: f32,
+    pub armor_damage: f32,
+    pub source: Vec3,
+}
+
+/// Per-player state for crouching, sliding and dashing.
+#[derive(Component)]
+pub struct MovementState {
+    pub is_crouching: bool,
+    pub slide_timer: f32,
+    /// Drag to restore once a slide ends.
+    pub standing_drag: f32,
+    pub dash_timer: f32,
+    pub dash_cooldown: f32,
+    pub dash_direction: Vec3,
+    pub eye_height: f32,
+}
+
+impl Default for MovementState {
+    fn default() -> Self {
+        Self {
+            is_crouching: false,
+            slide_timer: 0.0,
+            standing_drag: 0.95,
+            dash_timer: 0.0,
+            dash_cooldown: 0.0,
+            dash_direction: Vec3::ZERO,
+            eye_height: 1.4,
+        }
+    }
+}
+
+/// Body mesh drawn when the camera is outside the player's head.
+#[derive(Component)]
+pub struct PlayerModel;
+
+pub fn setup_player(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
     // Create player with physics components
     commands.spawn((
         TransformBundle::from(Transform::from_xyz(0.0, 1.0, 0.0)), // Start slightly above ground
+        VisibilityBundle::default(),
         Player::default(),
         PlayerBody,
         RigidBody {
@@ -67,63 +280,132 @@ fn setup_player(mut commands: Commands) {
             restitution: 0.0, // No bouncing for player
             drag: 0.95,
             is_kinematic: false,
+            ..default()
         },
         Collider {
-            radius: 0.5,
+            shape: ColliderShape::Capsule { radius: 0.5, half_height: 0.4 },
             collision_layer: CollisionLayer::Player,
             collision_mask: CollisionLayer::Zombie.mask() | CollisionLayer::Environment.mask(),
         },
         GroundDetector::default(),
-        Jumper {
-            can_jump: true,
-            jump_count: 0,
-            max_jumps: 1, // Single jump for now
-        },
-    ));
+        // One jump to start with; the extra-jump upgrade adds more
+        Jumper::default(),
+        MovementState::default(),
+    ))
+    .with_children(|parent| {
+        // Hidden in first person; matches the collider capsule
+        parent.spawn((
+            PbrBundle {
+                mesh: meshes.add(Mesh::from(Capsule3d::new(0.5, 0.8))),
+                material: materials.add(StandardMaterial {
+                    base_color: Color::srgb(0.0, 1.0, 1.0),
+                    emissive: Color::srgb(0.0, 1.5, 1.5).into(),
+                    ..default()
+                }),
+                visibility: Visibility::Hidden,
+                ..default()
+            },
+            PlayerModel,
+        ));
+    });
 }
 
 fn player_movement(
-    keyboard_input: Res<ButtonInput<KeyCode>>,
-    mut player_query: Query<(&Transform, &mut RigidBody, &mut Player, &GroundDetector), (With<Player>, Without<GameCamera>)>,
+    action_state: Res<ActionState<PlayerAction>>,
+    mut player_query: Query<(&Transform, &mut RigidBody, &mut Player, &mut MovementState, &GroundDetector), (With<Player>, Without<GameCamera>)>,
     camera_query: Query<&Transform, (With<GameCamera>, Without<Player>)>,
     config: Res<GameConfig>,
+    gamepad_config: Res<GamepadConfig>,
+    movement_settings: Res<MovementSettings>,
+    stamina_settings: Res<StaminaSettings>,
     time: Res<Time>,
 ) {
-    if let (Ok((_player_transform, mut rigidbody, _player, ground_detector)), Ok(camera_transform)) = 
+    if let (Ok((_player_transform, mut rigidbody, mut player, mut movement, ground_detector)), Ok(camera_transform)) = 
         (player_query.get_single_mut(), camera_query.get_single()) 
     {
+        let dt = time.delta_seconds();
         let mut direction = Vec3::ZERO;
         let forward = camera_transform.forward();
         let right = camera_transform.right();
 
         // Get movement input
-        if keyboard_input.pressed(KeyCode::KeyW) {
+        if action_state.pressed(&PlayerAction::MoveForward) {
             direction += *forward;
         }
-        if keyboard_input.pressed(KeyCode::KeyS) {
+        if action_state.pressed(&PlayerAction::MoveBackward) {
             direction -= *forward;
         }
-        if keyboard_input.pressed(KeyCode::KeyA) {
+        if action_state.pressed(&PlayerAction::MoveLeft) {
             direction -= *right;
         }
-        if keyboard_input.pressed(KeyCode::KeyD) {
+        if action_state.pressed(&PlayerAction::MoveRight) {
             direction += *right;
         }
 
         // Flatten direction to horizontal plane
         direction.y = 0.0;
+        direction = direction.normalize_or_zero();
 
-        // Sprinting
-        let speed_multiplier = if keyboard_input.pressed(KeyCode::ShiftLeft) {
-            1.5
+        // The left stick keeps its deflection so a light push walks slowly
+        let stick = apply_stick_response(
+            action_state.axis_pair(&PlayerAction::Move),
+            gamepad_config.move_deadzone,
+            gamepad_config.move_curve,
+        );
+        let flat_forward = Vec3::new(forward.x, 0.0, forward.z).normalize_or_zero();
+        direction += flat_forward * stick.y + *right * stick.x;
+        direction = direction.clamp_length_max(1.0);
+
+        // Dashing: a short burst in the movement direction, or straight ahead when standing still
+        movement.dash_cooldown = (movement.dash_cooldown - dt).max(0.0);
+        if action_state.just_pressed(&PlayerAction::Dash)
+            && movement.dash_cooldown <= 0.0
+            && player.has_stamina(stamina_settings.dash_cost)
+        {
+            player.spend_stamina(stamina_settings.dash_cost);
+            movement.dash_direction = if direction.length() > 0.0 {
+                direction.normalize()
+            } else {
+                flat_forward
+            };
+            movement.dash_timer = movement_settings.dash_duration;
+            movement.dash_cooldown = movement_settings.dash_cooldown;
+        }
+        if movement.dash_timer > 0.0 {
+            movement.dash_timer -= dt;
+            let dash_velocity = movement.dash_direction * movement_settings.dash_speed;
+            rigidbody.velocity.x = dash_velocity.x;
+            rigidbody.velocity.z = dash_velocity.z;
+            return;
+        }
+
+        // Sliding keeps its momentum; steering input is ignored until it ends
+        if movement.slide_timer > 0.0 {
+            return;
+        }
+
+        // Crouching slows the player down and cancels sprinting; sprinting burns stamina
+        let is_sprinting = action_state.pressed(&PlayerAction::Sprint)
+            && !movement.is_crouching
+            && !player.is_exhausted
+            && direction.length() > 0.0;
+        let mut speed_multiplier = if movement.is_crouching {
+            movement_settings.crouch_multiplier
+        } else if is_sprinting {
+            movement_settings.sprint_multiplier
         } else {
             1.0
         };
+        if is_sprinting {
+            player.spend_stamina(stamina_settings.sprint_drain * dt);
+        }
+        if player.is_exhausted {
+            speed_multiplier *= stamina_settings.exhausted_speed_multiplier;
+        }
+        speed_multiplier *= player.speed_multiplier;
 
         // Apply physics-based movement
         if direction.length() > 0.0 {
-            direction = direction.normalize();
-            
             // Calculate target velocity
             let target_velocity = direction * config.movement_speed * speed_multiplier;
             
@@ -135,16 +417,17 @@ fn player_movement(
             };
             
             // Smoothly accelerate towards target velocity
+            let previous_speed = Vec3::new(rigidbody.velocity.x, 0.0, rigidbody.velocity.z).length();
             let velocity_change = target_velocity - Vec3::new(rigidbody.velocity.x, 0.0, rigidbody.velocity.z);
-            let acceleration_force = velocity_change * acceleration * time.delta_seconds();
+            let acceleration_force = velocity_change * acceleration * dt;
             
             // Apply force to horizontal movement only
             rigidbody.velocity.x += acceleration_force.x;
             rigidbody.velocity.z += acceleration_force.z;
             
-            // Clamp horizontal velocity to max speed
+            // Clamp horizontal velocity to max speed, but keep any extra momentum from dashes, slides and jump pads
             let horizontal_velocity = Vec3::new(rigidbody.velocity.x, 0.0, rigidbody.velocity.z);
-            let max_speed = config.movement_speed * speed_multiplier;
+            let max_speed = (config.movement_speed * speed_multiplier).max(previous_speed);
             
             if horizontal_velocity.length() > max_speed {
                 let clamped = horizontal_velocity.normalize() * max_speed;
@@ -153,25 +436,181 @@ fn player_movement(
             }
         } else if ground_detector.is_grounded {
             // Apply stopping force when no input
-            let stopping_force = 0.9;
+            let stopping_force = damping_factor(0.9, dt);
             rigidbody.velocity.x *= stopping_force;
             rigidbody.velocity.z *= stopping_force;
         }
     }
 }
 
-fn handle_shooting(
-    mouse_input: Res<ButtonInput<MouseButton>>,
+/// Crouching changes the collider height, so it runs on the physics step alongside collision.
+fn update_crouch(
+    action_state: Res<ActionState<PlayerAction>>,
+    movement_settings: Res<MovementSettings>,
+    config: Res<GameConfig>,
+    mut player_set: ParamSet<(
+        Query<(Entity, &mut Transform, &mut Collider, &mut RigidBody, &mut MovementState, &GroundDetector, &Player)>,
+        SpatialQuery,
+    )>,
+    time: Res<Time>,
+) {
+    let dt = time.delta_seconds();
+    let height_change = movement_settings.standing_half_height - movement_settings.crouching_half_height;
+
+    let Some((entity, position, radius, is_crouching, sliding)) = player_set
+        .p0()
+        .get_single()
+        .ok()
+        .and_then(|(entity, transform, collider, _, movement, _, _)| match collider.shape {
+            ColliderShape::Capsule { radius, .. } => Some((entity, transform.translation, radius, movement.is_crouching, movement.slide_timer > 0.0)),
+            _ => None,
+        })
+    else {
+        return;
+    };
+
+    let wants_crouch = action_state.pressed(&PlayerAction::Crouch);
+
+    // Standing back up needs headroom above the crouched capsule
+    let blocked_above = is_crouching && !wants_crouch && {
+        let top = position + Vec3::Y * movement_settings.crouching_half_height;
+        let filter = SpatialQueryFilter::from_mask(CollisionLayer::Environment.mask()).with_excluded(entity);
+        player_set
+            .p1()
+            .cast_sphere(top, radius * 0.95, Vec3::Y, height_change * 2.0, &filter)
+            .is_some()
+    };
+
+    let mut player_query = player_set.p0();
+    let Ok((_, mut transform, mut collider, mut rigidbody, mut movement, ground_detector, player)) = player_query.get_single_mut() else {
+        return;
+    };
+
+    if wants_crouch && !is_crouching {
+        movement.is_crouching = true;
+        collider.shape = ColliderShape::Capsule { radius, half_height: movement_settings.crouching_half_height };
+        // Keep the feet on the ground; in the air the legs tuck up instead.
+        // The eye offset absorbs the jump so the camera still eases down.
+        if ground_detector.is_grounded {
+            transform.translation.y -= height_change;
+            movement.eye_height += height_change;
+        }
+
+        // Crouching while sprinting fast turns into a slide
+        let horizontal_velocity = Vec3::new(rigidbody.velocity.x, 0.0, rigidbody.velocity.z);
+        let slide_speed = config.movement_speed * movement_settings.slide_min_speed_factor;
+        let is_sprinting = action_state.pressed(&PlayerAction::Sprint) && !player.is_exhausted;
+        if ground_detector.is_grounded && is_sprinting && horizontal_velocity.length() >= slide_speed {
+            movement.slide_timer = movement_settings.slide_duration;
+            movement.standing_drag = rigidbody.drag;
+            rigidbody.drag = movement_settings.slide_drag;
+            let boost = horizontal_velocity.normalize() * movement_settings.slide_boost;
+            rigidbody.velocity.x += boost.x;
+            rigidbody.velocity.z += boost.z;
+        }
+    } else if !wants_crouch && is_crouching && !blocked_above {
+        movement.is_crouching = false;
+        collider.shape = ColliderShape::Capsule { radius, half_height: movement_settings.standing_half_height };
+        if ground_detector.is_grounded {
+            transform.translation.y += height_change;
+            movement.eye_height -= height_change;
+        }
+    }
+
+    if sliding {
+        movement.slide_timer -= dt;
+        let horizontal_speed = Vec3::new(rigidbody.velocity.x, 0.0, rigidbody.velocity.z).length();
+        let crouch_speed = config.movement_speed * movement_settings.crouch_multiplier;
+
+        // The slide ends when it runs out, slows to a crawl or the player stands or leaves the ground
+        if movement.slide_timer <= 0.0 || horizontal_speed < crouch_speed || !wants_crouch || !ground_detector.is_grounded {
+            movement.slide_timer = 0.0;
+            rigidbody.drag = movement.standing_drag;
+        }
+    }
+}
+
+fn regenerate_stamina(
+    mut player_query: Query<&mut Player>,
+    stamina_settings: Res<StaminaSettings>,
+    time: Res<Time>,
+) {
+    if let Ok(mut player) = player_query.get_single_mut() {
+        let dt = time.delta_seconds();
+        player.time_since_stamina_use += dt;
+
+        if player.time_since_stamina_use >= stamina_settings.regen_delay {
+            player.stamina = (player.stamina + stamina_settings.regen_rate * dt).min(player.max_stamina);
+        }
+        if player.is_exhausted && player.stamina >= stamina_settings.exhaustion_recovery {
+            player.is_exhausted = false;
+        }
+    }
+}
+
+fn apply_player_damage(
+    mut damage_events: EventReader<PlayerDamageEvent>,
+    mut hit_events: EventWriter<PlayerHitEvent>,
+    mut player_query: Query<&mut Player>,
+    damage_settings: Res<DamageSettings>,
+) {
+    let Ok(mut player) = player_query.get_single_mut() else {
+        return;
+    };
+
+    for event in damage_events.read() {
+        // Brief invulnerability after each hit stops a crowd from draining health instantly
+        if player.invulnerability_timer > 0.0 || player.is_invincible || player.health <= 0.0 {
+            continue;
+        }
+
+        let armor_damage = (event.amount * damage_settings.armor_absorption).min(player.armor);
+        let health_damage = event.amount - armor_damage;
+        player.armor -= armor_damage;
+        player.health = (player.health - health_damage).max(0.0);
+        player.time_since_damage = 0.0;
+        player.invulnerability_timer = damage_settings.invulnerability_duration;
+
+        hit_events.send(PlayerHitEvent {
+            health_damage,
+            armor_damage,
+            source: event.source,
+        });
+    }
+}
+
+fn regenerate_player_health(
+    mut player_query: Query<&mut Player>,
+    damage_settings: Res<DamageSettings>,
+    time: Res<Time>,
+) {
+    if let Ok(mut player) = player_query.get_single_mut() {
+        let dt = time.delta_seconds();
+        player.time_since_damage += dt;
+        player.invulnerability_timer = (player.invulnerability_timer - dt).max(0.0);
+
+        // Health and armor only come back after a while out of combat
+        if player.time_since_damage >= damage_settings.regen_delay && player.health > 0.0 {
+            player.health = (player.health + damage_settings.regen_rate * dt).min(player.max_health);
+            player.armor = (player.armor + damage_settings.armor_regen_rate * dt).min(player.max_armor);
+        }
+    }
+}
+
+pub fn handle_shooting(
+    action_state: Res<ActionState<PlayerAction>>,
     mut player_query: Query<&mut Player>,
     time: Res<Time>,
 ) {
     if let Ok(mut player) = player_query.get_single_mut() {
         let current_time = time.elapsed_seconds();
+        player.shot_fired = false;
         
-        if mouse_input.pressed(MouseButton::Left) {
-            // Rapid fire - 10 shots per second
-            if current_time - player.last_shot > 0.1 {
+        if action_state.pressed(&PlayerAction::Shoot) {
+            // Fire as fast as the current weapon allows
+            if current_time - player.last_shot > player.weapon.fire_interval() {
                 player.is_shooting = true;
+                player.shot_fired = true;
                 player.last_shot = current_time;
                 
                 // Increase psychedelic charge when shooting
@@ -186,46 +625,43 @@ fn handle_shooting(
 }
 
 fn update_player_effects(
-    mut player_query: Query<&mut Player>,
-    mut camera_query: Query<&mut Transform, With<GameCamera>>,
+    player_query: Query<&Player>,
     mut config: ResMut<GameConfig>,
-    time: Res<Time>,
 ) {
-    if let (Ok(player), Ok(mut camera_transform)) = 
-        (player_query.get_single_mut(), camera_query.get_single_mut()) 
-    {
+    if let Ok(player) = player_query.get_single() {
         // Update psychedelic intensity based on player state
         config.psychedelic_intensity = 0.5 + player.psychedelic_charge * 0.5;
-        
-        // Screen shake when shooting
-        if player.is_shooting {
-            let shake_strength = 0.01;
-            let shake_x = (time.elapsed_seconds() * 50.0).sin() * shake_strength;
-            let shake_y = (time.elapsed_seconds() * 60.0).cos() * shake_strength;
-            
-            camera_transform.translation += Vec3::new(shake_x, shake_y, 0.0);
-        }
     }
 }
 
 // Sync camera position with player position
 pub fn sync_camera_to_player(
-    player_query: Query<&Transform, (With<Player>, Without<GameCamera>)>,
+    mut player_query: Query<(&Transform, &mut MovementState), (With<Player>, Without<GameCamera>)>,
     mut camera_query: Query<&mut Transform, (With<GameCamera>, Without<Player>)>,
+    movement_settings: Res<MovementSettings>,
+    time: Res<Time>,
 ) {
-    if let (Ok(player_transform), Ok(mut camera_transform)) = 
-        (player_query.get_single(), camera_query.get_single_mut()) 
+    if let (Ok((player_transform, mut movement)), Ok(mut camera_transform)) = 
+        (player_query.get_single_mut(), camera_query.get_single_mut()) 
     {
+        // Ease the eye between standing and crouching height
+        let target_eye_height = if movement.is_crouching {
+            movement_settings.crouching_eye_height
+        } else {
+            movement_settings.standing_eye_height
+        };
+        let blend = 1.0 - (-movement_settings.eye_height_speed * time.delta_seconds()).exp();
+        movement.eye_height += (target_eye_height - movement.eye_height) * blend;
+
         camera_transform.translation.x = player_transform.translation.x;
         camera_transform.translation.z = player_transform.translation.z;
-        // Keep camera at eye height (1.8 units above player)
-        camera_transform.translation.y = player_transform.translation.y + 1.8;
+        camera_transform.translation.y = player_transform.translation.y + movement.eye_height;
     }
 }
 
 fn cleanup_player(mut commands: Commands, player_query: Query<Entity, With<Player>>) {
     for entity in player_query.iter() {
-        commands.entity(entity).despawn();
+        commands.entity(entity).despawn_recursive();
     }
 }
 
@@ -0,0 +1,277 @@
+use bevy::{
+    core_pipeline::{
+        core_3d::graph::{Core3d, Node3d},
+        fullscreen_vertex_shader::fullscreen_shader_vertex_state,
+    },
+    ecs::query::QueryItem,
+    prelude::*,
+    render::{
+        extract_component::{
+            ComponentUniforms, DynamicUniformIndex, ExtractComponent, ExtractComponentPlugin,
+            UniformComponentPlugin,
+        },
+        render_graph::{
+            NodeRunError, RenderGraphApp, RenderGraphContext, RenderLabel, ViewNode, ViewNodeRunner,
+        },
+        render_resource::{
+            binding_types::{sampler, texture_2d, uniform_buffer},
+            *,
+        },
+        renderer::{RenderContext, RenderDevice},
+        texture::BevyDefault,
+        view::ViewTarget,
+        RenderApp,
+    },
+};
+use crate::{GameCamera, GameConfig, InRun, camera::FirstPersonCamera};
+
+const SHADER_PATH: &str = "shaders/psychedelic_post_process.wgsl";
+
+pub struct PostProcessPlugin;
+
+impl Plugin for PostProcessPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PostProcessSettings>()
+            .add_plugins((
+                ExtractComponentPlugin::<PsychedelicPostProcess>::default(),
+                UniformComponentPlugin::<PsychedelicPostProcess>::default(),
+            ))
+            .add_systems(Update, update_post_process);
+
+        let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+
+        // Runs after tonemapping so the effects work on the final colours
+        render_app
+            .add_render_graph_node::<ViewNodeRunner<PsychedelicPostProcessNode>>(Core3d, PsychedelicPostProcessLabel)
+            .add_render_graph_edges(
+                Core3d,
+                (
+                    Node3d::Tonemapping,
+                    PsychedelicPostProcessLabel,
+                    Node3d::EndMainPassPostProcessing,
+                ),
+            );
+    }
+
+    fn finish(&self, app: &mut App) {
+        let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+
+        render_app.init_resource::<PsychedelicPostProcessPipeline>();
+    }
+}
+
+#[derive(Resource)]
+pub struct PostProcessSettings {
+    pub enabled: bool,
+    /// Scales every effect; photo mode exposes it as a slider.
+    pub strength: f32,
+    /// Constant barrel distortion at full psychedelic intensity, on top of the camera's animated distortion.
+    pub fisheye: f32,
+    /// Screen-space ripple, as a fraction of the screen.
+    pub wave_amplitude: f32,
+    /// Psychedelic intensity above which the kaleidoscope fades in.
+    pub kaleidoscope_threshold: f32,
+    pub kaleidoscope_segments: f32,
+    /// How quickly the effects ease out when leaving a run.
+    pub fade_speed: f32,
+}
+
+impl Default for PostProcessSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            strength: 1.0,
+            fisheye: 0.08,
+            wave_amplitude: 0.004,
+            kaleidoscope_threshold: 0.85,
+            kaleidoscope_segments: 6.0,
+            fade_speed: 3.0,
+        }
+    }
+}
+
+/// Per-camera uniform read by the post-process shader; field order must match the WGSL struct.
+#[derive(Component, Default, Clone, Copy, ExtractComponent, ShaderType)]
+pub struct PsychedelicPostProcess {
+    pub distortion: f32,
+    pub chromatic_aberration: f32,
+    pub fisheye: f32,
+    pub wave_amplitude: f32,
+    /// 0 to 1 blend toward the mirrored image.
+    pub kaleidoscope: f32,
+    pub kaleidoscope_segments: f32,
+    pub intensity: f32,
+    pub time: f32,
+}
+
+/// Feeds the camera's animated distortion and the current psychedelic intensity to the shader.
+fn update_post_process(
+    settings: Res<PostProcessSettings>,
+    config: Res<GameConfig>,
+    in_run: Option<Res<State<InRun>>>,
+    mut camera_query: Query<(&FirstPersonCamera, &mut PsychedelicPostProcess), With<GameCamera>>,
+    time: Res<Time>,
+    real_time: Res<Time<Real>>,
+) {
+    let Ok((camera, mut post_process)) = camera_query.get_single_mut() else {
+        return;
+    };
+
+    // Menus get a clean image; the effects ease back out rather than cutting off
+    // Real time so the fade still follows the photo mode sliders while the game clock is stopped
+    let target_intensity = if settings.enabled && in_run.is_some() {
+        config.psychedelic_intensity
+    } else {
+        0.0
+    };
+    let blend = 1.0 - (-settings.fade_speed * real_time.delta_seconds()).exp();
+    post_process.intensity += (target_intensity - post_process.intensity) * blend;
+
+    let fade = if config.psychedelic_intensity > 0.0 {
+        post_process.intensity / config.psychedelic_intensity
+    } else {
+        0.0
+    };
+    let intensity = post_process.intensity * settings.strength;
+    let strength = fade * settings.strength;
+    let kaleidoscope_range = (1.0 - settings.kaleidoscope_threshold).max(f32::EPSILON);
+
+    post_process.distortion = camera.distortion_strength * strength;
+    post_process.chromatic_aberration = camera.chromatic_aberration * strength;
+    post_process.fisheye = settings.fisheye * intensity;
+    post_process.wave_amplitude = settings.wave_amplitude * intensity;
+    post_process.kaleidoscope = ((intensity - settings.kaleidoscope_threshold) / kaleidoscope_range).clamp(0.0, 1.0);
+    post_process.kaleidoscope_segments = settings.kaleidoscope_segments;
+    post_process.time = time.elapsed_seconds();
+}
+
+#[derive(Debug, Hash, PartialEq, Eq, Clone, RenderLabel)]
+struct PsychedelicPostProcessLabel;
+
+#[derive(Default)]
+struct PsychedelicPostProcessNode;
+
+impl ViewNode for PsychedelicPostProcessNode {
+    type ViewQuery = (
+        &'static ViewTarget,
+        &'static DynamicUniformIndex<PsychedelicPostProcess>,
+    );
+
+    fn run(
+        &self,
+        _graph: &mut RenderGraphContext,
+        render_context: &mut RenderContext,
+        (view_target, settings_index): QueryItem<Self::ViewQuery>,
+        world: &World,
+    ) -> Result<(), NodeRunError> {
+        let post_process_pipeline = world.resource::<PsychedelicPostProcessPipeline>();
+        let pipeline_cache = world.resource::<PipelineCache>();
+
+        // The shader may still be loading
+        let Some(pipeline) = pipeline_cache.get_render_pipeline(post_process_pipeline.pipeline_id) else {
+            return Ok(());
+        };
+
+        let settings_uniforms = world.resource::<ComponentUniforms<PsychedelicPostProcess>>();
+        let Some(settings_binding) = settings_uniforms.uniforms().binding() else {
+            return Ok(());
+        };
+
+        // Swaps the view's ping-pong textures: read what's been rendered so far, write the result
+        let post_process = view_target.post_process_write();
+
+        let bind_group = render_context.render_device().create_bind_group(
+            "psychedelic_post_process_bind_group",
+            &post_process_pipeline.layout,
+            &BindGroupEntries::sequential((
+                post_process.source,
+                &post_process_pipeline.sampler,
+                settings_binding.clone(),
+            )),
+        );
+
+        let mut render_pass = render_context.begin_tracked_render_pass(RenderPassDescriptor {
+            label: Some("psychedelic_post_process_pass"),
+            color_attachments: &[Some(RenderPassColorAttachment {
+                view: post_process.destination,
+                resolve_target: None,
+                ops: Operations::default(),
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+
+        render_pass.set_render_pipeline(pipeline);
+        render_pass.set_bind_group(0, &bind_group, &[settings_index.index()]);
+        render_pass.draw(0..3, 0..1);
+
+        Ok(())
+    }
+}
+
+#[derive(Resource)]
+struct PsychedelicPostProcessPipeline {
+    layout: BindGroupLayout,
+    sampler: Sampler,
+    pipeline_id: CachedRenderPipelineId,
+}
+
+impl FromWorld for PsychedelicPostProcessPipeline {
+    fn from_world(world: &mut World) -> Self {
+        let render_device = world.resource::<RenderDevice>();
+
+        let layout = render_device.create_bind_group_layout(
+            "psychedelic_post_process_bind_group_layout",
+            &BindGroupLayoutEntries::sequential(
+                ShaderStages::FRAGMENT,
+                (
+                    texture_2d(TextureSampleType::Float { filterable: true }),
+                    sampler(SamplerBindingType::Filtering),
+                    uniform_buffer::<PsychedelicPostProcess>(true),
+                ),
+            ),
+        );
+
+        // Linear filtering keeps distorted lookups smooth; the default clamp-to-edge smears past the border
+        let sampler = render_device.create_sampler(&SamplerDescriptor {
+            mag_filter: FilterMode::Linear,
+            min_filter: FilterMode::Linear,
+            ..default()
+        });
+
+        let shader = world.load_asset(SHADER_PATH);
+
+        let pipeline_id = world
+            .resource_mut::<PipelineCache>()
+            .queue_render_pipeline(RenderPipelineDescriptor {
+                label: Some("psychedelic_post_process_pipeline".into()),
+                layout: vec![layout.clone()],
+                vertex: fullscreen_shader_vertex_state(),
+                fragment: Some(FragmentState {
+                    shader,
+                    shader_defs: vec![],
+                    entry_point: "fragment".into(),
+                    targets: vec![Some(ColorTargetState {
+                        format: TextureFormat::bevy_default(),
+                        blend: None,
+                        write_mask: ColorWrites::ALL,
+                    })],
+                }),
+                primitive: PrimitiveState::default(),
+                depth_stencil: None,
+                multisample: MultisampleState::default(),
+                push_constant_ranges: vec![],
+            });
+
+        Self {
+            layout,
+            sampler,
+            pipeline_id,
+        }
+    }
+}
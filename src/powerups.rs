@@ -0,0 +1,341 @@
+use bevy::prelude::*;
+use rand::Rng;
+use crate::{GameState, InRun, Player, Zombie, graphics::ARENA_HALF_SIZE, physics::*, weapons::{damage_zombie, ZombieKilled}};
+
+pub struct PowerUpPlugin;
+
+impl Plugin for PowerUpPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PowerUpSettings>()
+            .init_resource::<ActivePowerUps>()
+            .add_systems(OnEnter(InRun), setup_power_up_spawner)
+            .add_systems(
+                Update,
+                (
+                    spawn_power_ups,
+                    animate_power_ups,
+                    collect_power_ups,
+                    (tick_active_power_ups, apply_power_up_effects).chain(),
+                ).run_if(in_state(GameState::InGame)),
+            )
+            .add_systems(OnExit(InRun), cleanup_power_ups);
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PowerUpKind {
+    DoubleDamage,
+    SpeedBoost,
+    Invincibility,
+    InfiniteCharge,
+    Magnet,
+    Nuke,
+}
+
+impl PowerUpKind {
+    pub const ALL: [PowerUpKind; 6] = [
+        PowerUpKind::DoubleDamage,
+        PowerUpKind::SpeedBoost,
+        PowerUpKind::Invincibility,
+        PowerUpKind::InfiniteCharge,
+        PowerUpKind::Magnet,
+        PowerUpKind::Nuke,
+    ];
+
+    /// Seconds the buff lasts; the nuke goes off instantly and has no buff.
+    pub fn duration(&self) -> f32 {
+        match self {
+            PowerUpKind::DoubleDamage => 10.0,
+            PowerUpKind::SpeedBoost => 8.0,
+            PowerUpKind::Invincibility => 6.0,
+            PowerUpKind::InfiniteCharge => 12.0,
+            PowerUpKind::Magnet => 15.0,
+            PowerUpKind::Nuke => 0.0,
+        }
+    }
+
+    /// Short tag shown on the HUD buff icon.
+    pub fn icon_label(&self) -> &'static str {
+        match self {
+            PowerUpKind::DoubleDamage => "2X",
+            PowerUpKind::SpeedBoost => "SPD",
+            PowerUpKind::Invincibility => "INV",
+            PowerUpKind::InfiniteCharge => "CHG",
+            PowerUpKind::Magnet => "MAG",
+            PowerUpKind::Nuke => "NUKE",
+        }
+    }
+
+    pub fn color(&self) -> Color {
+        match self {
+            PowerUpKind::DoubleDamage => Color::srgb(1.0, 0.2, 0.2),
+            PowerUpKind::SpeedBoost => Color::srgb(0.2, 0.6, 1.0),
+            PowerUpKind::Invincibility => Color::srgb(1.0, 0.85, 0.1),
+            PowerUpKind::InfiniteCharge => Color::srgb(1.0, 0.0, 1.0),
+            PowerUpKind::Magnet => Color::srgb(0.3, 1.0, 0.4),
+            PowerUpKind::Nuke => Color::srgb(1.0, 0.5, 0.0),
+        }
+    }
+}
+
+#[derive(Resource)]
+pub struct PowerUpSettings {
+    pub spawn_interval: f32,
+    pub max_pickups: usize,
+    /// Seconds an uncollected pickup stays in the arena.
+    pub pickup_lifetime: f32,
+    pub pickup_radius: f32,
+    /// Distance kept from walls and the player when choosing a spawn spot.
+    pub spawn_margin: f32,
+    pub magnet_radius: f32,
+    pub magnet_speed: f32,
+    pub damage_multiplier: f32,
+    pub speed_multiplier: f32,
+    pub nuke_impulse: f32,
+}
+
+impl Default for PowerUpSettings {
+    fn default() -> Self {
+        Self {
+            spawn_interval: 12.0,
+            max_pickups: 3,
+            pickup_lifetime: 20.0,
+            pickup_radius: 1.5,
+            spawn_margin: 4.0,
+            magnet_radius: 15.0,
+            magnet_speed: 12.0,
+            damage_multiplier: 2.0,
+            speed_multiplier: 1.4,
+            nuke_impulse: 300.0,
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct PowerUpPickup {
+    pub kind: PowerUpKind,
+    pub lifetime: f32,
+    /// Hover height the pickup bobs around.
+    pub base_height: f32,
+}
+
+#[derive(Resource)]
+pub struct PowerUpSpawnTimer {
+    pub timer: Timer,
+}
+
+pub struct ActiveBuff {
+    pub kind: PowerUpKind,
+    pub remaining: f32,
+}
+
+/// Timed buffs currently applied to the player; picking up a buff again refreshes its timer.
+#[derive(Resource, Default)]
+pub struct ActivePowerUps {
+    pub buffs: Vec<ActiveBuff>,
+}
+
+impl ActivePowerUps {
+    pub fn is_active(&self, kind: PowerUpKind) -> bool {
+        self.buffs.iter().any(|buff| buff.kind == kind)
+    }
+
+    fn activate(&mut self, kind: PowerUpKind) {
+        match self.buffs.iter_mut().find(|buff| buff.kind == kind) {
+            Some(buff) => buff.remaining = kind.duration(),
+            None => self.buffs.push(ActiveBuff { kind, remaining: kind.duration() }),
+        }
+    }
+}
+
+fn setup_power_up_spawner(mut commands: Commands, settings: Res<PowerUpSettings>) {
+    commands.insert_resource(PowerUpSpawnTimer {
+        timer: Timer::from_seconds(settings.spawn_interval, TimerMode::Repeating),
+    });
+}
+
+fn spawn_power_ups(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut spawn_timer: ResMut<PowerUpSpawnTimer>,
+    pickup_query: Query<(), With<PowerUpPickup>>,
+    player_query: Query<&Transform, With<Player>>,
+    spatial_query: SpatialQuery,
+    settings: Res<PowerUpSettings>,
+    time: Res<Time>,
+) {
+    spawn_timer.timer.tick(time.delta());
+    if !spawn_timer.timer.just_finished() || pickup_query.iter().len() >= settings.max_pickups {
+        return;
+    }
+
+    let mut rng = rand::thread_rng();
+    let player_position = player_query.get_single().map(|transform| transform.translation).ok();
+    let extent = ARENA_HALF_SIZE - settings.spawn_margin;
+    let filter = SpatialQueryFilter::from_mask(CollisionLayer::Environment.mask());
+
+    // Pick an open spot on the floor, away from ledges, platforms and the player
+    let spawn_position = (0..10).find_map(|_| {
+        let candidate = Vec3::new(rng.gen_range(-extent..extent), 1.0, rng.gen_range(-extent..extent));
+        let near_player = player_position
+            .is_some_and(|position| position.distance(candidate) < settings.spawn_margin);
+        let blocked = !spatial_query.overlap_sphere(candidate, 0.8, &filter).is_empty();
+        (!near_player && !blocked).then_some(candidate)
+    });
+    let Some(spawn_position) = spawn_position else {
+        return;
+    };
+
+    let kind = PowerUpKind::ALL[rng.gen_range(0..PowerUpKind::ALL.len())];
+    let color = kind.color().to_linear();
+
+    commands.spawn((
+        PbrBundle {
+            mesh: meshes.add(Mesh::from(Cuboid::new(0.6, 0.6, 0.6))),
+            material: materials.add(StandardMaterial {
+                base_color: kind.color(),
+                emissive: LinearRgba::rgb(color.red * 3.0, color.green * 3.0, color.blue * 3.0),
+                ..default()
+            }),
+            transform: Transform::from_translation(spawn_position)
+                .with_rotation(Quat::from_rotation_x(std::f32::consts::FRAC_PI_4)),
+            ..default()
+        },
+        PowerUpPickup {
+            kind,
+            lifetime: 0.0,
+            base_height: spawn_position.y,
+        },
+    ));
+}
+
+fn animate_power_ups(
+    mut commands: Commands,
+    mut pickup_query: Query<(Entity, &mut Transform, &mut PowerUpPickup), Without<Player>>,
+    player_query: Query<&Transform, With<Player>>,
+    active_power_ups: Res<ActivePowerUps>,
+    settings: Res<PowerUpSettings>,
+    time: Res<Time>,
+) {
+    let dt = time.delta_seconds();
+    let player_position = player_query.get_single().map(|transform| transform.translation).ok();
+    let magnet = active_power_ups.is_active(PowerUpKind::Magnet);
+
+    for (entity, mut transform, mut pickup) in pickup_query.iter_mut() {
+        pickup.lifetime += dt;
+        if pickup.lifetime >= settings.pickup_lifetime {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        // Spin and bob so pickups stand out from the arena
+        transform.rotate_y(dt * 2.0);
+        transform.translation.y = pickup.base_height + (pickup.lifetime * 3.0).sin() * 0.2;
+
+        // The magnet drags nearby pickups toward the player
+        if let (true, Some(player_position)) = (magnet, player_position) {
+            let to_player = player_position - transform.translation;
+            if to_player.length() < settings.magnet_radius {
+                let step = to_player.normalize_or_zero() * settings.magnet_speed * dt;
+                transform.translation.x += step.x;
+                transform.translation.z += step.z;
+            }
+        }
+    }
+}
+
+fn collect_power_ups(
+    mut commands: Commands,
+    pickup_query: Query<(Entity, &Transform, &PowerUpPickup), Without<Player>>,
+    player_query: Query<&Transform, With<Player>>,
+    mut zombie_query: Query<(&Transform, &mut Zombie, &mut RigidBody), Without<Player>>,
+    mut active_power_ups: ResMut<ActivePowerUps>,
+    settings: Res<PowerUpSettings>,
+    mut kill_events: EventWriter<ZombieKilled>,
+) {
+    let Ok(player_transform) = player_query.get_single() else {
+        return;
+    };
+
+    for (entity, transform, pickup) in pickup_query.iter() {
+        if transform.translation.distance(player_transform.translation) > settings.pickup_radius {
+            continue;
+        }
+        commands.entity(entity).despawn();
+        info!("Picked up {:?}", pickup.kind);
+
+        if pickup.kind != PowerUpKind::Nuke {
+            active_power_ups.activate(pickup.kind);
+            continue;
+        }
+
+        // Nuke: every zombie in the arena dies, blown away from the player
+        for (zombie_transform, mut zombie, mut rigidbody) in zombie_query.iter_mut() {
+            if zombie.health <= 0.0 {
+                continue;
+            }
+
+            let away = (zombie_transform.translation - player_transform.translation).normalize_or_zero();
+            let impulse = (away + Vec3::Y * 0.5).normalize() * settings.nuke_impulse;
+            let damage = zombie.health;
+            damage_zombie(&mut zombie, &mut rigidbody, zombie_transform.translation, damage, impulse, &mut kill_events);
+        }
+    }
+}
+
+fn tick_active_power_ups(
+    mut active_power_ups: ResMut<ActivePowerUps>,
+    time: Res<Time>,
+) {
+    if active_power_ups.buffs.is_empty() {
+        return;
+    }
+
+    let dt = time.delta_seconds();
+    for buff in active_power_ups.buffs.iter_mut() {
+        buff.remaining -= dt;
+    }
+    active_power_ups.buffs.retain(|buff| {
+        if buff.remaining <= 0.0 {
+            info!("{:?} wore off", buff.kind);
+        }
+        buff.remaining > 0.0
+    });
+}
+
+fn apply_power_up_effects(
+    mut player_query: Query<&mut Player>,
+    active_power_ups: Res<ActivePowerUps>,
+    settings: Res<PowerUpSettings>,
+) {
+    let Ok(mut player) = player_query.get_single_mut() else {
+        return;
+    };
+
+    player.damage_multiplier = if active_power_ups.is_active(PowerUpKind::DoubleDamage) {
+        settings.damage_multiplier
+    } else {
+        1.0
+    };
+    player.speed_multiplier = if active_power_ups.is_active(PowerUpKind::SpeedBoost) {
+        settings.speed_multiplier
+    } else {
+        1.0
+    };
+    player.is_invincible = active_power_ups.is_active(PowerUpKind::Invincibility);
+    if active_power_ups.is_active(PowerUpKind::InfiniteCharge) {
+        player.psychedelic_charge = 1.0;
+    }
+}
+
+fn cleanup_power_ups(
+    mut commands: Commands,
+    pickup_query: Query<Entity, With<PowerUpPickup>>,
+    mut active_power_ups: ResMut<ActivePowerUps>,
+) {
+    for entity in pickup_query.iter() {
+        commands.entity(entity).despawn();
+    }
+    active_power_ups.buffs.clear();
+}
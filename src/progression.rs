@@ -0,0 +1,275 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use crate::{
+    GameState, InRun, Player, RunResults,
+    perks::{apply_perk, PerkKind, RunPerks},
+    persistence::{load_ron, save_ron},
+    physics::Jumper,
+    player::setup_player,
+    weapons::{BulletModifiers, WeaponKind},
+    zombies::ZombieType,
+};
+
+/// Where currency and unlocks are saved between sessions.
+const PROFILE_PATH: &str = "profile.ron";
+
+pub struct ProgressionPlugin;
+
+impl Plugin for ProgressionPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(load_ron::<Profile>(PROFILE_PATH).unwrap_or_default())
+            .init_resource::<RunRewards>()
+            .add_systems(OnEnter(InRun), equip_profile_loadout.after(setup_player))
+            .add_systems(OnEnter(GameState::GameOver), award_run_currency);
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum UnlockCategory {
+    Weapons,
+    StartingPerks,
+    Codex,
+    Themes,
+}
+
+impl UnlockCategory {
+    pub const ALL: [UnlockCategory; 4] = [
+        UnlockCategory::Weapons,
+        UnlockCategory::StartingPerks,
+        UnlockCategory::Codex,
+        UnlockCategory::Themes,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            UnlockCategory::Weapons => "WEAPONS",
+            UnlockCategory::StartingPerks => "STARTING PERKS",
+            UnlockCategory::Codex => "CODEX",
+            UnlockCategory::Themes => "THEMES",
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum Unlock {
+    Blaster,
+    Scattergun,
+    RailRifle,
+    StartVitality,
+    StartPiercing,
+    StartExtraJump,
+    CodexBasic,
+    CodexFast,
+    CodexHeavy,
+    CodexExploder,
+    NeonTheme,
+    InfernoTheme,
+    ToxicTheme,
+}
+
+pub struct UnlockDefinition {
+    pub unlock: Unlock,
+    pub category: UnlockCategory,
+    pub name: &'static str,
+    /// Zero-cost unlocks are owned from the start.
+    pub cost: u32,
+    /// Node that must be bought first.
+    pub requires: Option<Unlock>,
+}
+
+pub const UNLOCKS: [UnlockDefinition; 13] = [
+    UnlockDefinition { unlock: Unlock::Blaster, category: UnlockCategory::Weapons, name: "Blaster", cost: 0, requires: None },
+    UnlockDefinition { unlock: Unlock::Scattergun, category: UnlockCategory::Weapons, name: "Scattergun", cost: 60, requires: None },
+    UnlockDefinition { unlock: Unlock::RailRifle, category: UnlockCategory::Weapons, name: "Rail Rifle", cost: 150, requires: Some(Unlock::Scattergun) },
+    UnlockDefinition { unlock: Unlock::StartVitality, category: UnlockCategory::StartingPerks, name: "Vitality", cost: 40, requires: None },
+    UnlockDefinition { unlock: Unlock::StartPiercing, category: UnlockCategory::StartingPerks, name: "Piercing Rounds", cost: 100, requires: Some(Unlock::StartVitality) },
+    UnlockDefinition { unlock: Unlock::StartExtraJump, category: UnlockCategory::StartingPerks, name: "Extra Jump", cost: 180, requires: Some(Unlock::StartPiercing) },
+    UnlockDefinition { unlock: Unlock::CodexBasic, category: UnlockCategory::Codex, name: "Cube Walker", cost: 10, requires: None },
+    UnlockDefinition { unlock: Unlock::CodexFast, category: UnlockCategory::Codex, name: "Orb Runner", cost: 20, requires: Some(Unlock::CodexBasic) },
+    UnlockDefinition { unlock: Unlock::CodexHeavy, category: UnlockCategory::Codex, name: "Monolith", cost: 30, requires: Some(Unlock::CodexFast) },
+    UnlockDefinition { unlock: Unlock::CodexExploder, category: UnlockCategory::Codex, name: "Flare", cost: 40, requires: Some(Unlock::CodexHeavy) },
+    UnlockDefinition { unlock: Unlock::NeonTheme, category: UnlockCategory::Themes, name: "Neon", cost: 0, requires: None },
+    UnlockDefinition { unlock: Unlock::InfernoTheme, category: UnlockCategory::Themes, name: "Inferno", cost: 50, requires: None },
+    UnlockDefinition { unlock: Unlock::ToxicTheme, category: UnlockCategory::Themes, name: "Toxic", cost: 80, requires: Some(Unlock::InfernoTheme) },
+];
+
+impl Unlock {
+    pub fn definition(&self) -> &'static UnlockDefinition {
+        UNLOCKS
+            .iter()
+            .find(|definition| definition.unlock == *self)
+            .expect("every unlock has a definition")
+    }
+
+    pub fn weapon(&self) -> Option<WeaponKind> {
+        match self {
+            Unlock::Blaster => Some(WeaponKind::Blaster),
+            Unlock::Scattergun => Some(WeaponKind::Scattergun),
+            Unlock::RailRifle => Some(WeaponKind::RailRifle),
+            _ => None,
+        }
+    }
+
+    pub fn starting_perk(&self) -> Option<PerkKind> {
+        match self {
+            Unlock::StartVitality => Some(PerkKind::Vitality),
+            Unlock::StartPiercing => Some(PerkKind::PiercingRounds),
+            Unlock::StartExtraJump => Some(PerkKind::ExtraJump),
+            _ => None,
+        }
+    }
+
+    pub fn codex_entry(&self) -> Option<ZombieType> {
+        match self {
+            Unlock::CodexBasic => Some(ZombieType::Basic),
+            Unlock::CodexFast => Some(ZombieType::Fast),
+            Unlock::CodexHeavy => Some(ZombieType::Heavy),
+            Unlock::CodexExploder => Some(ZombieType::Exploder),
+            _ => None,
+        }
+    }
+
+    pub fn theme(&self) -> Option<Theme> {
+        match self {
+            Unlock::NeonTheme => Some(Theme::Neon),
+            Unlock::InfernoTheme => Some(Theme::Inferno),
+            Unlock::ToxicTheme => Some(Theme::Toxic),
+            _ => None,
+        }
+    }
+}
+
+/// Lore shown on the codex page once an entry is unlocked.
+pub fn codex_description(zombie_type: &ZombieType) -> &'static str {
+    match zombie_type {
+        ZombieType::Basic => "A shambling cube of bad geometry. 30 health, slow, hits for 10.",
+        ZombieType::Fast => "A glowing orb that rolls straight for you. Only 15 health, but twice as quick.",
+        ZombieType::Heavy => "A slab of violet stone. 60 health, barely moves, hits for 20.",
+        ZombieType::Exploder => "Burns bright and dies fast. 10 health, but its 50 damage reaches 5 metres.",
+    }
+}
+
+/// Cosmetic palette for bullets.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+pub enum Theme {
+    #[default]
+    Neon,
+    Inferno,
+    Toxic,
+}
+
+impl Theme {
+    /// Multiplier applied to the bullet glow.
+    pub fn bullet_tint(&self) -> Vec3 {
+        match self {
+            Theme::Neon => Vec3::ONE,
+            Theme::Inferno => Vec3::new(1.3, 0.45, 0.2),
+            Theme::Toxic => Vec3::new(0.3, 1.2, 0.4),
+        }
+    }
+}
+
+#[derive(Resource, Default, Serialize, Deserialize)]
+pub struct Profile {
+    pub currency: u32,
+    pub lifetime_currency: u32,
+    pub unlocks: Vec<Unlock>,
+    #[serde(default)]
+    pub weapon: WeaponKind,
+    #[serde(default)]
+    pub theme: Theme,
+}
+
+impl Profile {
+    pub fn has(&self, unlock: Unlock) -> bool {
+        unlock.definition().cost == 0 || self.unlocks.contains(&unlock)
+    }
+
+    pub fn can_buy(&self, unlock: Unlock) -> bool {
+        let definition = unlock.definition();
+        !self.has(unlock)
+            && definition.requires.map_or(true, |required| self.has(required))
+            && self.currency >= definition.cost
+    }
+
+    /// Buys `unlock` if it's affordable and its prerequisite is owned.
+    pub fn buy(&mut self, unlock: Unlock) -> bool {
+        if !self.can_buy(unlock) {
+            return false;
+        }
+
+        self.currency -= unlock.definition().cost;
+        self.unlocks.push(unlock);
+        true
+    }
+
+    /// Selects an owned weapon or theme; other unlocks are always active.
+    /// Returns whether the selection changed.
+    pub fn equip(&mut self, unlock: Unlock) -> bool {
+        if !self.has(unlock) || self.is_equipped(unlock) {
+            return false;
+        }
+        if let Some(weapon) = unlock.weapon() {
+            self.weapon = weapon;
+        }
+        if let Some(theme) = unlock.theme() {
+            self.theme = theme;
+        }
+        unlock.weapon().is_some() || unlock.theme().is_some()
+    }
+
+    pub fn is_equipped(&self, unlock: Unlock) -> bool {
+        unlock.weapon() == Some(self.weapon) || unlock.theme() == Some(self.theme)
+    }
+
+    pub fn save(&self) {
+        save_ron(PROFILE_PATH, self);
+    }
+}
+
+/// Currency earned by a run: one shard per 50 points plus one per kill.
+pub fn currency_for_run(score: f32, kills: u32) -> u32 {
+    (score / 50.0) as u32 + kills
+}
+
+/// What the last run paid out, shown on the game over screen.
+#[derive(Resource, Default)]
+pub struct RunRewards {
+    pub earned: u32,
+}
+
+pub fn award_run_currency(
+    run_results: Res<RunResults>,
+    mut profile: ResMut<Profile>,
+    mut rewards: ResMut<RunRewards>,
+) {
+    let earned = currency_for_run(run_results.score, run_results.kills);
+    rewards.earned = earned;
+    profile.currency += earned;
+    profile.lifetime_currency += earned;
+    profile.save();
+    info!("Earned {} shards ({} total)", earned, profile.currency);
+}
+
+/// Gives the freshly spawned player their selected weapon and unlocked starting perks.
+fn equip_profile_loadout(
+    profile: Res<Profile>,
+    mut player_query: Query<(&mut Player, &mut Jumper)>,
+    mut run_perks: ResMut<RunPerks>,
+    mut bullet_modifiers: ResMut<BulletModifiers>,
+) {
+    let Ok((mut player, mut jumper)) = player_query.get_single_mut() else {
+        return;
+    };
+
+    player.weapon = profile.weapon;
+    for definition in UNLOCKS.iter() {
+        let Some(kind) = definition.unlock.starting_perk() else {
+            continue;
+        };
+        if profile.has(definition.unlock) {
+            apply_perk(kind, &mut player, &mut jumper, &mut bullet_modifiers);
+            run_perks.owned.push(kind);
+        }
+    }
+}
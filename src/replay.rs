@@ -0,0 +1,321 @@
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use leafwing_input_manager::prelude::*;
+use std::collections::VecDeque;
+use crate::{GameCamera, GameState, InRun, Player, Zombie, controls::PlayerAction, player::PlayerModel, weapons::Bullet};
+
+pub struct ReplayPlugin;
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ReplaySettings>()
+            .init_resource::<ReplayBuffer>()
+            .add_systems(Update, record_replay_frame.run_if(in_state(GameState::InGame)))
+            .add_systems(OnEnter(GameState::KillCam), start_kill_cam)
+            .add_systems(Update, play_kill_cam.run_if(in_state(GameState::KillCam)))
+            .add_systems(OnExit(GameState::KillCam), end_kill_cam)
+            .add_systems(OnExit(InRun), clear_replay_buffer);
+    }
+}
+
+#[derive(Resource)]
+pub struct ReplaySettings {
+    /// Seconds of history kept for the kill cam.
+    pub duration: f32,
+    pub sample_interval: f32,
+    /// Below 1 plays the replay in slow motion.
+    pub playback_speed: f32,
+    /// Seconds spent on the final frame before the game over screen.
+    pub hold_time: f32,
+    pub orbit_distance: f32,
+    pub orbit_height: f32,
+    /// Radians per second the camera circles the player.
+    pub orbit_speed: f32,
+}
+
+impl Default for ReplaySettings {
+    fn default() -> Self {
+        Self {
+            duration: 4.0,
+            sample_interval: 1.0 / 30.0,
+            playback_speed: 0.5,
+            hold_time: 1.0,
+            orbit_distance: 6.0,
+            orbit_height: 2.5,
+            orbit_speed: 0.6,
+        }
+    }
+}
+
+struct ReplayFrame {
+    time: f32,
+    transforms: HashMap<Entity, Transform>,
+}
+
+/// Rolling history of the player, zombie and bullet transforms over the last few seconds.
+#[derive(Resource, Default)]
+pub struct ReplayBuffer {
+    frames: VecDeque<ReplayFrame>,
+    /// Mesh and material of every recorded entity, so ones that have since despawned can be redrawn.
+    appearances: HashMap<Entity, (Handle<Mesh>, Handle<StandardMaterial>)>,
+    player: Option<Entity>,
+    since_last_sample: f32,
+}
+
+impl ReplayBuffer {
+    /// Where `entity` was at `time`, interpolated between samples; `None` if it didn't exist then.
+    fn sample(&self, entity: Entity, time: f32) -> Option<Transform> {
+        let next_index = self.frames.partition_point(|frame| frame.time <= time);
+        let previous = self.frames.get(next_index.checked_sub(1)?)?.transforms.get(&entity)?;
+
+        let Some(next_frame) = self.frames.get(next_index) else {
+            return Some(*previous);
+        };
+        let Some(next) = next_frame.transforms.get(&entity) else {
+            return Some(*previous);
+        };
+
+        let previous_time = self.frames[next_index - 1].time;
+        let t = ((time - previous_time) / (next_frame.time - previous_time).max(f32::EPSILON)).clamp(0.0, 1.0);
+        Some(Transform {
+            translation: previous.translation.lerp(next.translation, t),
+            rotation: previous.rotation.slerp(next.rotation, t),
+            scale: previous.scale.lerp(next.scale, t),
+        })
+    }
+
+    fn time_range(&self) -> Option<(f32, f32)> {
+        Some((self.frames.front()?.time, self.frames.back()?.time))
+    }
+}
+
+/// Stand-in drawn during the kill cam for a recorded entity.
+#[derive(Component)]
+pub struct ReplayProxy {
+    pub source: Entity,
+}
+
+#[derive(Component)]
+pub struct KillCamUI;
+
+#[derive(Resource)]
+struct KillCamPlayback {
+    time: f32,
+    end: f32,
+    orbit_angle: f32,
+}
+
+fn record_replay_frame(
+    mut buffer: ResMut<ReplayBuffer>,
+    settings: Res<ReplaySettings>,
+    player_query: Query<(Entity, &Transform), With<Player>>,
+    recorded_query: Query<(Entity, &Transform, &Handle<Mesh>, &Handle<StandardMaterial>), Or<(With<Zombie>, With<Bullet>)>>,
+    time: Res<Time>,
+) {
+    buffer.since_last_sample += time.delta_seconds();
+    if buffer.since_last_sample < settings.sample_interval {
+        return;
+    }
+    buffer.since_last_sample = 0.0;
+
+    let now = time.elapsed_seconds();
+    let mut transforms = HashMap::new();
+    if let Ok((entity, transform)) = player_query.get_single() {
+        buffer.player = Some(entity);
+        transforms.insert(entity, *transform);
+    }
+    for (entity, transform, mesh, material) in recorded_query.iter() {
+        transforms.insert(entity, *transform);
+        buffer.appearances.entry(entity).or_insert_with(|| (mesh.clone(), material.clone()));
+    }
+
+    let ReplayBuffer { frames, appearances, .. } = &mut *buffer;
+    frames.push_back(ReplayFrame { time: now, transforms });
+
+    // Drop history older than the replay window, and the appearances only it referred to
+    let mut dropped_frames = false;
+    while frames.front().is_some_and(|frame| now - frame.time > settings.duration) {
+        frames.pop_front();
+        dropped_frames = true;
+    }
+    if dropped_frames {
+        appearances.retain(|entity, _| frames.iter().any(|frame| frame.transforms.contains_key(entity)));
+    }
+}
+
+fn start_kill_cam(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    buffer: Res<ReplayBuffer>,
+    mut live_query: Query<&mut Visibility, Or<(With<Zombie>, With<Bullet>, With<PlayerModel>)>>,
+) {
+    // The replay redraws everything from the buffer, so hide the live scene
+    for mut visibility in live_query.iter_mut() {
+        *visibility = Visibility::Hidden;
+    }
+
+    for (source, (mesh, material)) in buffer.appearances.iter() {
+        commands.spawn((
+            PbrBundle {
+                mesh: mesh.clone(),
+                material: material.clone(),
+                visibility: Visibility::Hidden,
+                ..default()
+            },
+            ReplayProxy { source: *source },
+        ));
+    }
+
+    // The player has no mesh of their own in first person
+    if let Some(player) = buffer.player {
+        commands.spawn((
+            PbrBundle {
+                mesh: meshes.add(Mesh::from(Capsule3d::new(0.5, 0.8))),
+                material: materials.add(StandardMaterial {
+                    base_color: Color::srgb(0.0, 1.0, 1.0),
+                    emissive: Color::srgb(0.0, 1.5, 1.5).into(),
+                    ..default()
+                }),
+                visibility: Visibility::Hidden,
+                ..default()
+            },
+            ReplayProxy { source: player },
+        ));
+    }
+
+    let (start, end) = buffer.time_range().unwrap_or_default();
+    commands.insert_resource(KillCamPlayback {
+        time: start,
+        end,
+        orbit_angle: 0.0,
+    });
+
+    commands.spawn((
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                top: Val::Px(40.0),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            ..default()
+        },
+        KillCamUI,
+    ))
+    .with_children(|parent| {
+        parent.spawn(TextBundle::from_section(
+            "KILL CAM",
+            TextStyle {
+                font_size: 56.0,
+                color: Color::srgb(1.0, 0.0, 0.3),
+                ..default()
+            },
+        ));
+        parent.spawn(TextBundle::from_section(
+            "Shoot or jump to skip",
+            TextStyle {
+                font_size: 20.0,
+                color: Color::srgb(0.8, 0.8, 0.8),
+                ..default()
+            },
+        ));
+    });
+}
+
+fn play_kill_cam(
+    mut playback: ResMut<KillCamPlayback>,
+    buffer: Res<ReplayBuffer>,
+    settings: Res<ReplaySettings>,
+    mut proxy_query: Query<(&ReplayProxy, &mut Transform, &mut Visibility), Without<GameCamera>>,
+    mut camera_query: Query<&mut Transform, With<GameCamera>>,
+    action_state: Res<ActionState<PlayerAction>>,
+    mut next_state: ResMut<NextState<GameState>>,
+    time: Res<Time>,
+) {
+    let dt = time.delta_seconds();
+    playback.time += dt * settings.playback_speed;
+    playback.orbit_angle += dt * settings.orbit_speed;
+
+    let skipped = [PlayerAction::Shoot, PlayerAction::Jump, PlayerAction::Pause]
+        .iter()
+        .any(|action| action_state.just_pressed(action));
+    if skipped || playback.time >= playback.end + settings.hold_time * settings.playback_speed {
+        next_state.set(GameState::GameOver);
+        return;
+    }
+
+    let sample_time = playback.time.min(playback.end);
+    for (proxy, mut transform, mut visibility) in proxy_query.iter_mut() {
+        match buffer.sample(proxy.source, sample_time) {
+            Some(sampled) => {
+                *transform = sampled;
+                *visibility = Visibility::Visible;
+            }
+            None => *visibility = Visibility::Hidden,
+        }
+    }
+
+    // Circle the player at a distance so whatever got them is in view
+    let player_transform = buffer.player.and_then(|player| buffer.sample(player, sample_time));
+    if let (Some(player_transform), Ok(mut camera_transform)) = (player_transform, camera_query.get_single_mut()) {
+        let target = player_transform.translation + Vec3::Y * 0.5;
+        let offset = Vec3::new(
+            playback.orbit_angle.cos() * settings.orbit_distance,
+            settings.orbit_height,
+            playback.orbit_angle.sin() * settings.orbit_distance,
+        );
+        camera_transform.translation = target + offset;
+        camera_transform.look_at(target, Vec3::Y);
+    }
+}
+
+fn end_kill_cam(
+    mut commands: Commands,
+    proxy_query: Query<Entity, Or<(With<ReplayProxy>, With<KillCamUI>)>>,
+) {
+    for entity in proxy_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    commands.remove_resource::<KillCamPlayback>();
+}
+
+fn clear_replay_buffer(mut buffer: ResMut<ReplayBuffer>) {
+    *buffer = ReplayBuffer::default();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn buffer_with(entity: Entity, samples: &[(f32, Vec3)]) -> ReplayBuffer {
+        let mut buffer = ReplayBuffer::default();
+        for &(time, translation) in samples {
+            let mut transforms = HashMap::default();
+            transforms.insert(entity, Transform::from_translation(translation));
+            buffer.frames.push_back(ReplayFrame { time, transforms });
+        }
+        buffer
+    }
+
+    #[test]
+    fn sample_interpolates_between_frames() {
+        let entity = Entity::from_raw(1);
+        let buffer = buffer_with(entity, &[(0.0, Vec3::ZERO), (1.0, Vec3::new(4.0, 0.0, 0.0))]);
+
+        let sampled = buffer.sample(entity, 0.25).unwrap();
+        assert!((sampled.translation - Vec3::new(1.0, 0.0, 0.0)).length() < 1e-5);
+    }
+
+    #[test]
+    fn sample_holds_the_last_frame_and_skips_unrecorded_times() {
+        let entity = Entity::from_raw(1);
+        let buffer = buffer_with(entity, &[(1.0, Vec3::ZERO), (2.0, Vec3::Y)]);
+
+        assert_eq!(buffer.sample(entity, 5.0).unwrap().translation, Vec3::Y);
+        assert!(buffer.sample(entity, 0.5).is_none());
+        assert!(buffer.sample(Entity::from_raw(2), 1.5).is_none());
+    }
+}
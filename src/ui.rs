@@ -1,5 +1,6 @@
 use bevy::prelude::*;
-use crate::{Player, GameConfig, GameState};
+use bevy::window::PrimaryWindow;
+use crate::{Player, PlayerHitEvent, GameCamera, GameConfig, GameState, InRun, powerups::{ActivePowerUps, PowerUpKind}};
 
 #[cfg(feature = "dev")]
 use iyes_perf_ui::prelude::*;
@@ -8,15 +9,19 @@ pub struct UIPlugin;
 
 impl Plugin for UIPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(GameState::InGame), setup_ui)
+        app.add_systems(OnEnter(InRun), setup_ui)
             .add_systems(Update, (
                 update_health_bar,
+                update_armor_bar,
+                update_stamina_bar,
+                update_buff_icons,
+                (spawn_damage_indicators, update_damage_indicators).chain(),
                 update_score_display,
                 update_kill_count_display,
                 update_crosshair,
                 update_ui_effects,
             ).run_if(in_state(GameState::InGame)))
-            .add_systems(OnExit(GameState::InGame), cleanup_ui);
+            .add_systems(OnExit(InRun), (cleanup_ui, cleanup_damage_indicators));
             
         #[cfg(feature = "dev")]
         app.add_systems(Startup, setup_perf_ui);
@@ -26,6 +31,43 @@ impl Plugin for UIPlugin {
 #[derive(Component)]
 pub struct HealthBar;
 
+#[derive(Component)]
+pub struct ArmorBar;
+
+#[derive(Component)]
+pub struct StaminaBar;
+
+const STAMINA_COLOR: Color = Color::srgb(0.2, 0.9, 0.3);
+const EXHAUSTED_COLOR: Color = Color::srgb(0.9, 0.3, 0.1);
+
+/// Red marker around the crosshair pointing toward whatever just hit the player.
+#[derive(Component)]
+pub struct DamageIndicator {
+    pub source: Vec3,
+    pub lifetime: f32,
+    pub max_lifetime: f32,
+}
+
+/// Distance of damage indicators from the screen center, in pixels.
+const DAMAGE_INDICATOR_RADIUS: f32 = 140.0;
+
+/// Row of active power-up icons above the stamina bar.
+#[derive(Component)]
+pub struct BuffBar;
+
+#[derive(Component)]
+pub struct BuffIcon {
+    pub kind: PowerUpKind,
+}
+
+#[derive(Component)]
+pub struct BuffCountdown {
+    pub kind: PowerUpKind,
+}
+
+#[derive(Component)]
+pub struct HudRoot;
+
 #[derive(Component)]
 pub struct ScoreText;
 
@@ -41,15 +83,18 @@ pub struct PsychedelicUI;
 fn setup_ui(mut commands: Commands) {
     // Root UI node
     commands
-        .spawn(NodeBundle {
-            style: Style {
-                width: Val::Percent(100.0),
-                height: Val::Percent(100.0),
-                position_type: PositionType::Absolute,
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    position_type: PositionType::Absolute,
+                    ..default()
+                },
                 ..default()
             },
-            ..default()
-        })
+            HudRoot,
+        ))
         .with_children(|parent| {
             // Health bar
             parent
@@ -82,6 +127,84 @@ fn setup_ui(mut commands: Commands) {
                     ));
                 });
 
+            // Armor bar, just above health
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        position_type: PositionType::Absolute,
+                        left: Val::Px(20.0),
+                        bottom: Val::Px(46.0),
+                        width: Val::Px(200.0),
+                        height: Val::Px(10.0),
+                        border: UiRect::all(Val::Px(2.0)),
+                        ..default()
+                    },
+                    background_color: Color::srgb(0.0, 0.1, 0.2).into(),
+                    border_color: Color::srgb(0.0, 0.6, 1.0).into(),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    parent.spawn((
+                        NodeBundle {
+                            style: Style {
+                                width: Val::Percent(100.0),
+                                height: Val::Percent(100.0),
+                                ..default()
+                            },
+                            background_color: Color::srgb(0.0, 0.6, 1.0).into(),
+                            ..default()
+                        },
+                        ArmorBar,
+                    ));
+                });
+
+            // Stamina bar, above armor
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        position_type: PositionType::Absolute,
+                        left: Val::Px(20.0),
+                        bottom: Val::Px(62.0),
+                        width: Val::Px(200.0),
+                        height: Val::Px(8.0),
+                        border: UiRect::all(Val::Px(2.0)),
+                        ..default()
+                    },
+                    background_color: Color::srgb(0.0, 0.15, 0.05).into(),
+                    border_color: STAMINA_COLOR.into(),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    parent.spawn((
+                        NodeBundle {
+                            style: Style {
+                                width: Val::Percent(100.0),
+                                height: Val::Percent(100.0),
+                                ..default()
+                            },
+                            background_color: STAMINA_COLOR.into(),
+                            ..default()
+                        },
+                        StaminaBar,
+                    ));
+                });
+
+            // Active power-ups, filled in by update_buff_icons
+            parent.spawn((
+                NodeBundle {
+                    style: Style {
+                        position_type: PositionType::Absolute,
+                        left: Val::Px(20.0),
+                        bottom: Val::Px(80.0),
+                        flex_direction: FlexDirection::Row,
+                        column_gap: Val::Px(6.0),
+                        ..default()
+                    },
+                    ..default()
+                },
+                BuffBar,
+            ));
+
             // Score display
             parent.spawn((
                 TextBundle::from_section(
@@ -173,6 +296,182 @@ fn update_health_bar(
     }
 }
 
+fn update_armor_bar(
+    player_query: Query<&Player>,
+    mut armor_bar_query: Query<&mut Style, With<ArmorBar>>,
+) {
+    if let (Ok(player), Ok(mut style)) = 
+        (player_query.get_single(), armor_bar_query.get_single_mut()) {
+        let armor_percent = (player.armor / player.max_armor) * 100.0;
+        style.width = Val::Percent(armor_percent);
+    }
+}
+
+fn update_stamina_bar(
+    player_query: Query<&Player>,
+    mut stamina_bar_query: Query<(&mut Style, &mut BackgroundColor), With<StaminaBar>>,
+) {
+    if let (Ok(player), Ok((mut style, mut background_color))) = 
+        (player_query.get_single(), stamina_bar_query.get_single_mut()) {
+        let stamina_percent = (player.stamina / player.max_stamina) * 100.0;
+        style.width = Val::Percent(stamina_percent);
+
+        // Turns red until the player has recovered from exhaustion
+        background_color.0 = if player.is_exhausted { EXHAUSTED_COLOR } else { STAMINA_COLOR };
+    }
+}
+
+fn update_buff_icons(
+    mut commands: Commands,
+    active_power_ups: Res<ActivePowerUps>,
+    bar_query: Query<Entity, With<BuffBar>>,
+    icon_query: Query<(Entity, &BuffIcon)>,
+    mut countdown_query: Query<(&BuffCountdown, &mut Text)>,
+) {
+    let Ok(bar) = bar_query.get_single() else {
+        return;
+    };
+
+    // Drop icons for buffs that wore off
+    for (entity, icon) in icon_query.iter() {
+        if !active_power_ups.is_active(icon.kind) {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+
+    // Add icons for newly collected buffs
+    for buff in active_power_ups.buffs.iter() {
+        if !icon_query.iter().any(|(_, icon)| icon.kind == buff.kind) {
+            commands.entity(bar).with_children(|parent| spawn_buff_icon(parent, buff.kind));
+        }
+    }
+
+    for (countdown, mut text) in countdown_query.iter_mut() {
+        if let Some(buff) = active_power_ups.buffs.iter().find(|buff| buff.kind == countdown.kind) {
+            text.sections[0].value = format!("{:.1}", buff.remaining.max(0.0));
+        }
+    }
+}
+
+fn spawn_buff_icon(parent: &mut ChildBuilder, kind: PowerUpKind) {
+    parent
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Px(48.0),
+                    height: Val::Px(48.0),
+                    border: UiRect::all(Val::Px(2.0)),
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                background_color: Color::srgba(0.0, 0.0, 0.0, 0.6).into(),
+                border_color: kind.color().into(),
+                ..default()
+            },
+            BuffIcon { kind },
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                kind.icon_label(),
+                TextStyle {
+                    font_size: 16.0,
+                    color: kind.color(),
+                    ..default()
+                },
+            ));
+            parent.spawn((
+                TextBundle::from_section(
+                    format!("{:.1}", kind.duration()),
+                    TextStyle {
+                        font_size: 14.0,
+                        color: Color::WHITE,
+                        ..default()
+                    },
+                ),
+                BuffCountdown { kind },
+            ));
+        });
+}
+
+fn spawn_damage_indicators(
+    mut commands: Commands,
+    mut hit_events: EventReader<PlayerHitEvent>,
+) {
+    for event in hit_events.read() {
+        commands.spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    ..default()
+                },
+                background_color: Color::srgba(1.0, 0.0, 0.0, 0.8).into(),
+                ..default()
+            },
+            DamageIndicator {
+                source: event.source,
+                lifetime: 0.0,
+                max_lifetime: 1.2,
+            },
+        ));
+    }
+}
+
+fn update_damage_indicators(
+    mut commands: Commands,
+    mut indicator_query: Query<(Entity, &mut DamageIndicator, &mut Style, &mut BackgroundColor)>,
+    player_query: Query<&Transform, With<Player>>,
+    camera_query: Query<&Transform, (With<GameCamera>, Without<Player>)>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    time: Res<Time>,
+) {
+    let (Ok(player_transform), Ok(camera_transform), Ok(window)) =
+        (player_query.get_single(), camera_query.get_single(), windows.get_single()) else {
+        return;
+    };
+    let screen_center = Vec2::new(window.width(), window.height()) * 0.5;
+
+    // Horizontal camera axes, so the indicator follows where the player is facing
+    let forward = camera_transform.forward();
+    let forward = Vec3::new(forward.x, 0.0, forward.z).normalize_or_zero();
+    let right = Vec3::new(-forward.z, 0.0, forward.x);
+
+    for (entity, mut indicator, mut style, mut background_color) in indicator_query.iter_mut() {
+        indicator.lifetime += time.delta_seconds();
+        if indicator.lifetime >= indicator.max_lifetime {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+
+        // Angle of the attacker clockwise from straight ahead, placed on a ring around the crosshair
+        let to_source = indicator.source - player_transform.translation;
+        let angle = to_source.dot(right).atan2(to_source.dot(forward));
+        let offset = Vec2::new(angle.sin(), -angle.cos()) * DAMAGE_INDICATOR_RADIUS;
+
+        // Stretch the marker along the ring
+        let size = if offset.x.abs() > offset.y.abs() {
+            Vec2::new(12.0, 60.0)
+        } else {
+            Vec2::new(60.0, 12.0)
+        };
+        let position = screen_center + offset - size * 0.5;
+        style.left = Val::Px(position.x);
+        style.top = Val::Px(position.y);
+        style.width = Val::Px(size.x);
+        style.height = Val::Px(size.y);
+
+        let fade = 1.0 - indicator.lifetime / indicator.max_lifetime;
+        background_color.0 = Color::srgba(1.0, 0.0, 0.0, 0.8 * fade);
+    }
+}
+
+fn cleanup_damage_indicators(mut commands: Commands, indicator_query: Query<Entity, With<DamageIndicator>>) {
+    for entity in indicator_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
 fn update_score_display(
     player_query: Query<&Player>,
     mut score_text_query: Query<&mut Text, With<ScoreText>>,
@@ -238,7 +537,7 @@ fn update_ui_effects(
     }
 }
 
-fn cleanup_ui(mut commands: Commands, ui_query: Query<Entity, With<PsychedelicUI>>) {
+fn cleanup_ui(mut commands: Commands, ui_query: Query<Entity, With<HudRoot>>) {
     for entity in ui_query.iter() {
         commands.entity(entity).despawn_recursive();
     }
@@ -1,20 +1,51 @@
 use bevy::prelude::*;
-use crate::{GameCamera, Player, Zombie, particles::spawn_death_effect};
+use bevy::utils::HashSet;
+use leafwing_input_manager::prelude::*;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use crate::{
+    GameCamera, GameState, InRun, Player, StaminaSettings, Zombie, ZombieType,
+    camera::{position_camera_rig, FirstPersonCamera},
+    camera_effects::{CameraTraumaEvent, TraumaKind},
+    controls::PlayerAction,
+    particles::spawn_death_effect,
+    physics::*,
+    player::handle_shooting,
+    progression::Profile,
+};
+
+const MELEE_DAMAGE: f32 = 40.0;
+const MELEE_RANGE: f32 = 1.5;
+const MELEE_RADIUS: f32 = 1.2;
+const MELEE_COOLDOWN: f32 = 0.6;
+const MELEE_KNOCKBACK: f32 = 400.0;
+const EXPLOSION_DAMAGE: f32 = 30.0;
+const EXPLOSION_KNOCKBACK: f32 = 250.0;
 
 pub struct WeaponsPlugin;
 
 impl Plugin for WeaponsPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(
-            Update,
-            (
-                spawn_bullets,
-                move_bullets,
-                bullet_collision,
-                cleanup_bullets,
-                update_bullet_effects,
-            ),
-        );
+        app.init_resource::<BulletModifiers>()
+            .add_event::<ZombieKilled>()
+            .add_event::<BulletExplosion>()
+            // Bullets are stepped with the physics so a fast one can't skip past a collider between contact checks
+            .add_systems(
+                FixedUpdate,
+                move_bullets.before(update_spatial_hash).run_if(in_state(GameState::InGame)),
+            )
+            .add_systems(
+                Update,
+                (
+                    spawn_bullets.after(handle_shooting).after(position_camera_rig),
+                    (bullet_collision, explode_bullets).chain(),
+                    cleanup_bullets,
+                    update_bullet_effects,
+                    handle_melee.after(position_camera_rig).run_if(melee_pressed),
+                    award_zombie_kills.after(explode_bullets).after(handle_melee),
+                ).run_if(in_state(GameState::InGame)),
+            )
+            .add_systems(OnExit(InRun), cleanup_all_bullets);
     }
 }
 
@@ -25,6 +56,12 @@ pub struct Bullet {
     pub lifetime: f32,
     pub max_lifetime: f32,
     pub trail_intensity: f32,
+    /// Impulse applied along the bullet's direction to whatever it hits.
+    pub knockback: f32,
+    /// Zombies the bullet passes through before it's spent.
+    pub pierce: u32,
+    /// Radius of the blast when the bullet kills a zombie; zero for none.
+    pub explosion_radius: f32,
 }
 
 impl Default for Bullet {
@@ -35,6 +72,79 @@ impl Default for Bullet {
             lifetime: 0.0,
             max_lifetime: 3.0,
             trail_intensity: 1.0,
+            knockback: 120.0,
+            pierce: 0,
+            explosion_radius: 0.0,
+        }
+    }
+}
+
+/// Guns unlocked through progression; the player picks one before a run.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+pub enum WeaponKind {
+    #[default]
+    Blaster,
+    Scattergun,
+    RailRifle,
+}
+
+impl WeaponKind {
+    /// Seconds between shots.
+    pub fn fire_interval(&self) -> f32 {
+        match self {
+            WeaponKind::Blaster => 0.1,
+            WeaponKind::Scattergun => 0.55,
+            WeaponKind::RailRifle => 0.8,
+        }
+    }
+
+    pub fn pellets(&self) -> u32 {
+        match self {
+            WeaponKind::Scattergun => 6,
+            _ => 1,
+        }
+    }
+
+    /// Largest random deviation of each pellet from the crosshair, in radians.
+    pub fn spread(&self) -> f32 {
+        match self {
+            WeaponKind::Scattergun => 0.08,
+            _ => 0.0,
+        }
+    }
+
+    pub fn damage(&self) -> f32 {
+        match self {
+            WeaponKind::Blaster => Bullet::default().damage,
+            WeaponKind::Scattergun => 12.0,
+            WeaponKind::RailRifle => 80.0,
+        }
+    }
+
+    pub fn pierce(&self) -> u32 {
+        match self {
+            WeaponKind::RailRifle => 3,
+            _ => 0,
+        }
+    }
+}
+
+/// Run-wide changes to every bullet fired, built up from perks.
+#[derive(Resource)]
+pub struct BulletModifiers {
+    pub damage_multiplier: f32,
+    pub knockback_multiplier: f32,
+    pub pierce: u32,
+    pub explosion_radius: f32,
+}
+
+impl Default for BulletModifiers {
+    fn default() -> Self {
+        Self {
+            damage_multiplier: 1.0,
+            knockback_multiplier: 1.0,
+            pierce: 0,
+            explosion_radius: 0.0,
         }
     }
 }
@@ -42,38 +152,123 @@ impl Default for Bullet {
 #[derive(Component)]
 pub struct BulletTrail;
 
+/// Sent when a bullet, explosion, melee swing or power-up kills a zombie.
+#[derive(Event)]
+pub struct ZombieKilled {
+    pub position: Vec3,
+    pub zombie_type: ZombieType,
+}
+
+/// An explosive bullet killed a zombie at `position`.
+#[derive(Event)]
+pub struct BulletExplosion {
+    pub position: Vec3,
+    pub radius: f32,
+}
+
+/// Hits a living zombie, knocking it back and sending `ZombieKilled` if the hit was fatal.
+/// Returns the health actually removed, so overkill doesn't count towards lifesteal.
+pub fn damage_zombie(
+    zombie: &mut Zombie,
+    rigidbody: &mut RigidBody,
+    position: Vec3,
+    damage: f32,
+    impulse: Vec3,
+    kill_events: &mut EventWriter<ZombieKilled>,
+) -> f32 {
+    let dealt = damage.min(zombie.health.max(0.0));
+    zombie.health -= damage;
+    zombie.last_hit_impulse = impulse;
+    rigidbody.apply_impulse(impulse);
+
+    if zombie.health <= 0.0 {
+        kill_events.send(ZombieKilled { position, zombie_type: zombie.zombie_type.clone() });
+    }
+    dealt
+}
+
+/// Scores every kill and bursts the body into particles.
+fn award_zombie_kills(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut kill_events: EventReader<ZombieKilled>,
+    mut player_query: Query<&mut Player>,
+) {
+    let mut player = player_query.get_single_mut().ok();
+
+    for event in kill_events.read() {
+        if let Some(player) = player.as_mut() {
+            player.kill_count += 1;
+            player.score += 50.0;
+        }
+        spawn_death_effect(&mut commands, &mut meshes, &mut materials, event.position, &event.zombie_type);
+    }
+}
+
 fn spawn_bullets(
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
     player_query: Query<&Player>,
-    camera_query: Query<&Transform, With<GameCamera>>,
+    camera_query: Query<&FirstPersonCamera, With<GameCamera>>,
+    bullet_modifiers: Res<BulletModifiers>,
+    profile: Res<Profile>,
 ) {
-    if let (Ok(player), Ok(camera_transform)) = 
+    if let (Ok(player), Ok(camera)) = 
         (player_query.get_single(), camera_query.get_single()) 
     {
-        if player.is_shooting {
-            let forward = camera_transform.forward();
-            let bullet_spawn = camera_transform.translation + forward * 1.0;
-            
-            // Create psychedelic bullet
+        // Aim from the eye so third-person shots leave the player, not the camera behind them
+        let camera_transform = &camera.eye;
+        if player.shot_fired {
+            let mut rng = rand::thread_rng();
+            let weapon = player.weapon;
+            let tint = profile.theme.bullet_tint();
             let bullet_mesh = meshes.add(Mesh::from(Sphere::new(0.1)));
-            let bullet_material = materials.add(StandardMaterial {
-                base_color: Color::srgb(1.0, 1.0, 0.0),
-                emissive: Color::srgb(2.0, 2.0, 0.5).into(),
-                ..default()
-            });
-            
-            commands.spawn((
-                PbrBundle {
-                    mesh: bullet_mesh,
-                    material: bullet_material,
-                    transform: Transform::from_translation(bullet_spawn)
-                        .looking_to(forward, Vec3::Y),
+
+            for _ in 0..weapon.pellets() {
+                // Scatter pellets inside the weapon's spread cone
+                let spread = weapon.spread();
+                let forward = if spread > 0.0 {
+                    camera_transform.rotation
+                        * Quat::from_euler(EulerRot::YXZ, rng.gen_range(-spread..spread), rng.gen_range(-spread..spread), 0.0)
+                        * Vec3::NEG_Z
+                } else {
+                    *camera_transform.forward()
+                };
+                let bullet_spawn = camera_transform.translation + forward * 1.0;
+
+                // Create psychedelic bullet
+                let bullet_material = materials.add(StandardMaterial {
+                    base_color: Color::srgb(tint.x.min(1.0), tint.y.min(1.0), 0.0),
+                    emissive: Color::srgb(2.0 * tint.x, 2.0 * tint.y, 0.5 * tint.z).into(),
                     ..default()
-                },
-                Bullet::default(),
-            ));
+                });
+
+                commands.spawn((
+                    PbrBundle {
+                        mesh: bullet_mesh.clone(),
+                        material: bullet_material,
+                        transform: Transform::from_translation(bullet_spawn)
+                            .looking_to(forward, Vec3::Y),
+                        ..default()
+                    },
+                    Bullet {
+                        damage: weapon.damage() * player.damage_multiplier * bullet_modifiers.damage_multiplier,
+                        knockback: Bullet::default().knockback * bullet_modifiers.knockback_multiplier,
+                        pierce: weapon.pierce() + bullet_modifiers.pierce,
+                        explosion_radius: bullet_modifiers.explosion_radius,
+                        ..default()
+                    },
+                    Collider {
+                        shape: ColliderShape::Sphere { radius: 0.4 },
+                        collision_layer: CollisionLayer::Bullet,
+                        collision_mask: CollisionLayer::Zombie.mask() | CollisionLayer::Environment.mask(),
+                    },
+                    Sensor,
+                    PhysicsInterpolation::default(),
+                ));
+            }
         }
     }
 }
@@ -89,47 +284,138 @@ fn move_bullets(
 }
 
 fn bullet_collision(
+    mut commands: Commands,
+    mut collision_events: EventReader<CollisionStarted>,
+    mut bullet_query: Query<(&Transform, &mut Bullet), Without<Zombie>>,
+    mut zombie_query: Query<(&Transform, &mut Zombie, &mut RigidBody), Without<Bullet>>,
+    mut player_query: Query<&mut Player>,
+    mut kill_events: EventWriter<ZombieKilled>,
+    mut explosion_events: EventWriter<BulletExplosion>,
+) {
+    let mut spent_bullets = HashSet::new();
+
+    for event in collision_events.read() {
+        let Some((bullet_entity, other_entity)) = order_pair(event.entity_a, event.entity_b, |entity| bullet_query.contains(entity)) else {
+            continue;
+        };
+        // A spent bullet doesn't hit anything else this frame
+        if spent_bullets.contains(&bullet_entity) {
+            continue;
+        }
+        let Ok((bullet_transform, mut bullet)) = bullet_query.get_mut(bullet_entity) else {
+            continue;
+        };
+
+        if let Ok((zombie_transform, mut zombie, mut rigidbody)) = zombie_query.get_mut(other_entity) {
+            // Already killed by another bullet this frame
+            if zombie.health <= 0.0 {
+                continue;
+            }
+
+            // Damage zombie and knock it back
+            let impulse = *bullet_transform.forward() * bullet.knockback;
+            let dealt = damage_zombie(&mut zombie, &mut rigidbody, zombie_transform.translation, bullet.damage, impulse, &mut kill_events);
+
+            if let Ok(mut player) = player_query.get_single_mut() {
+                player.reward_hit(dealt);
+                player.psychedelic_charge = (player.psychedelic_charge + 0.2).min(1.0);
+            }
+
+            if zombie.health <= 0.0 && bullet.explosion_radius > 0.0 {
+                explosion_events.send(BulletExplosion {
+                    position: zombie_transform.translation,
+                    radius: bullet.explosion_radius,
+                });
+            }
+
+            // Piercing bullets carry on through the zombie
+            if bullet.pierce > 0 {
+                bullet.pierce -= 1;
+                continue;
+            }
+        }
+
+        // Remove bullet, whether it hit a zombie or the environment
+        spent_bullets.insert(bullet_entity);
+        commands.entity(bullet_entity).despawn();
+    }
+}
+
+/// Explosive kills hurt every zombie caught in the blast.
+fn explode_bullets(
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
-    bullet_query: Query<(Entity, &Transform), (With<Bullet>, Without<Zombie>)>,
-    mut zombie_query: Query<(Entity, &Transform, &mut Zombie), Without<Bullet>>,
-    mut player_query: Query<&mut Player>,
+    mut explosion_events: EventReader<BulletExplosion>,
+    mut zombie_query: Query<(&Transform, &mut Zombie, &mut RigidBody)>,
+    mut kill_events: EventWriter<ZombieKilled>,
+    mut trauma_events: EventWriter<CameraTraumaEvent>,
 ) {
-    if let Ok(mut player) = player_query.get_single_mut() {
-        for (bullet_entity, bullet_transform) in bullet_query.iter() {
-            for (_zombie_entity, zombie_transform, mut zombie) in zombie_query.iter_mut() {
-                let distance = bullet_transform.translation.distance(zombie_transform.translation);
-                
-                if distance < 1.0 { // Hit detection radius
-                    // Damage zombie
-                    zombie.health -= 25.0;
-                    
-                    // Increase player score and psychedelic charge
-                    player.score += 10.0;
-                    player.psychedelic_charge = (player.psychedelic_charge + 0.2).min(1.0);
-                    
-                    // If zombie dies, increase kill count
-                    if zombie.health <= 0.0 {
-                        player.kill_count += 1;
-                        player.score += 50.0;
-                        
-                        // Spawn comprehensive death effect with particle system
-                        spawn_death_effect(
-                            &mut commands,
-                            &mut meshes,
-                            &mut materials,
-                            zombie_transform.translation,
-                            &zombie.zombie_type,
-                        );
-                    }
-                    
-                    // Remove bullet
-                    commands.entity(bullet_entity).despawn();
-                    break;
-                }
+    for explosion in explosion_events.read() {
+        let center = explosion.position;
+        spawn_death_effect(&mut commands, &mut meshes, &mut materials, center, &ZombieType::Exploder);
+        trauma_events.send(CameraTraumaEvent { kind: TraumaKind::Explosion, position: center });
+
+        for (zombie_transform, mut zombie, mut rigidbody) in zombie_query.iter_mut() {
+            let offset = zombie_transform.translation - center;
+            if zombie.health <= 0.0 || offset.length() > explosion.radius {
+                continue;
             }
+
+            let impulse = (offset.normalize_or_zero() + Vec3::Y * 0.5).normalize() * EXPLOSION_KNOCKBACK;
+            damage_zombie(&mut zombie, &mut rigidbody, zombie_transform.translation, EXPLOSION_DAMAGE, impulse, &mut kill_events);
+        }
+    }
+}
+
+fn cleanup_all_bullets(mut commands: Commands, bullet_query: Query<Entity, With<Bullet>>) {
+    for entity in bullet_query.iter() {
+        commands.entity(entity).despawn();
+    }
+}
+
+fn melee_pressed(action_state: Res<ActionState<PlayerAction>>) -> bool {
+    action_state.just_pressed(&PlayerAction::Melee)
+}
+
+/// Swings at every zombie in a sphere just in front of the player's eye, costing stamina.
+fn handle_melee(
+    mut player_query: Query<&mut Player>,
+    camera_query: Query<&FirstPersonCamera, With<GameCamera>>,
+    mut zombie_query: Query<(&Transform, &mut Zombie, &mut RigidBody)>,
+    spatial_query: SpatialQuery,
+    stamina_settings: Res<StaminaSettings>,
+    mut kill_events: EventWriter<ZombieKilled>,
+    time: Res<Time>,
+) {
+    let (Ok(mut player), Ok(camera)) = (player_query.get_single_mut(), camera_query.get_single()) else {
+        return;
+    };
+
+    let current_time = time.elapsed_seconds();
+    if current_time - player.last_melee < MELEE_COOLDOWN || !player.has_stamina(stamina_settings.melee_cost) {
+        return;
+    }
+    player.last_melee = current_time;
+    player.spend_stamina(stamina_settings.melee_cost);
+
+    let forward = *camera.eye.forward();
+    let center = camera.eye.translation + forward * MELEE_RANGE;
+    let filter = SpatialQueryFilter::from_mask(CollisionLayer::Zombie.mask());
+
+    for entity in spatial_query.overlap_sphere(center, MELEE_RADIUS, &filter) {
+        let Ok((zombie_transform, mut zombie, mut rigidbody)) = zombie_query.get_mut(entity) else {
+            continue;
+        };
+        if zombie.health <= 0.0 {
+            continue;
         }
+
+        // Knock the zombie away from the player, slightly upward
+        let impulse = (forward + Vec3::Y * 0.3).normalize() * MELEE_KNOCKBACK;
+        let damage = MELEE_DAMAGE * player.damage_multiplier;
+        let dealt = damage_zombie(&mut zombie, &mut rigidbody, zombie_transform.translation, damage, impulse, &mut kill_events);
+        player.reward_hit(dealt);
     }
 }
 
@@ -150,8 +436,11 @@ fn cleanup_bullets(
 fn update_bullet_effects(
     mut bullet_query: Query<(&mut Handle<StandardMaterial>, &Bullet)>,
     mut materials: ResMut<Assets<StandardMaterial>>,
+    profile: Res<Profile>,
     time: Res<Time>,
 ) {
+    let tint = profile.theme.bullet_tint();
+
     for (material_handle, bullet) in bullet_query.iter_mut() {
         if let Some(material) = materials.get_mut(&*material_handle) {
             let phase = time.elapsed_seconds() * 10.0;
@@ -160,9 +449,9 @@ fn update_bullet_effects(
             // Psychedelic bullet colors with lifetime factor
             let lifetime_factor = 1.0 - (bullet.lifetime / bullet.max_lifetime);
             material.emissive = Color::srgb(
-                (2.0 + pulse * 0.5) * lifetime_factor,
-                (2.0 + (phase * 1.2).cos() * 0.5) * lifetime_factor,
-                (0.5 + (phase * 0.8).sin() * 0.5) * lifetime_factor,
+                (2.0 + pulse * 0.5) * lifetime_factor * tint.x,
+                (2.0 + (phase * 1.2).cos() * 0.5) * lifetime_factor * tint.y,
+                (0.5 + (phase * 0.8).sin() * 0.5) * lifetime_factor * tint.z,
             ).into();
         }
     }
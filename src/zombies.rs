@@ -1,24 +1,25 @@
 use bevy::prelude::*;
 use rand::Rng;
-use crate::{GameConfig, Player, GameState, physics::*};
+use crate::{GameConfig, Player, GameState, InRun, camera_effects::{CameraTraumaEvent, TraumaKind}, physics::*, debris::*};
 
 pub struct ZombiePlugin;
 
 impl Plugin for ZombiePlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(GameState::InGame), setup_zombie_system)
+        app.add_event::<WaveCompletedEvent>()
+            .add_systems(OnEnter(InRun), setup_zombie_system)
             .add_systems(
                 Update,
                 (
                     spawn_zombies,
                     zombie_ai,
                     zombie_movement,
-                    zombie_attack,
                     cleanup_dead_zombies,
+                    check_wave_complete.after(cleanup_dead_zombies),
                     update_zombie_effects,
                 ).run_if(in_state(GameState::InGame)),
             )
-            .add_systems(OnExit(GameState::InGame), cleanup_all_zombies);
+            .add_systems(OnExit(InRun), cleanup_all_zombies);
     }
 }
 
@@ -27,12 +28,13 @@ pub struct Zombie {
     pub health: f32,
     pub max_health: f32,
     pub speed: f32,
-    pub damage: f32,
+    /// How close the zombie gets before it stops chasing; contact damage is dealt by `CollisionDamage`.
     pub attack_range: f32,
-    pub last_attack: f32,
     pub zombie_type: ZombieType,
     pub pulsation_phase: f32,
     pub color_shift: f32,
+    /// Impulse of the most recent hit, passed on to the debris when the zombie dies.
+    pub last_hit_impulse: Vec3,
 }
 
 #[derive(Clone)]
@@ -49,12 +51,11 @@ impl Default for Zombie {
             health: 30.0,
             max_health: 30.0,
             speed: 2.0,
-            damage: 10.0,
             attack_range: 2.0,
-            last_attack: 0.0,
             zombie_type: ZombieType::Basic,
             pulsation_phase: 0.0,
             color_shift: 0.0,
+            last_hit_impulse: Vec3::ZERO,
         }
     }
 }
@@ -88,8 +89,32 @@ impl Default for ZombieSpawnTimer {
     }
 }
 
+/// Progress through the current wave; a wave ends once all of its zombies have spawned and died.
+#[derive(Resource)]
+pub struct ZombieWave {
+    pub number: u32,
+    pub to_spawn: u32,
+    pub spawned: u32,
+}
+
+impl ZombieWave {
+    pub fn new(number: u32) -> Self {
+        Self {
+            number,
+            to_spawn: 6 + number * 4,
+            spawned: 0,
+        }
+    }
+}
+
+#[derive(Event)]
+pub struct WaveCompletedEvent {
+    pub wave: u32,
+}
+
 fn setup_zombie_system(mut commands: Commands) {
     commands.init_resource::<ZombieSpawnTimer>();
+    commands.insert_resource(ZombieWave::new(1));
 }
 
 fn spawn_zombies(
@@ -97,13 +122,18 @@ fn spawn_zombies(
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
     mut spawn_timer: ResMut<ZombieSpawnTimer>,
+    mut wave: ResMut<ZombieWave>,
     zombie_query: Query<&Zombie>,
     _config: Res<GameConfig>,
     time: Res<Time>,
 ) {
     spawn_timer.timer.tick(time.delta());
     
-    if spawn_timer.timer.just_finished() && zombie_query.iter().count() < spawn_timer.max_zombies {
+    if spawn_timer.timer.just_finished()
+        && zombie_query.iter().count() < spawn_timer.max_zombies
+        && wave.spawned < wave.to_spawn
+    {
+        wave.spawned += 1;
         let mut rng = rand::thread_rng();
         let spawn_point = spawn_timer.spawn_points[rng.gen_range(0..spawn_timer.spawn_points.len())];
         
@@ -152,7 +182,6 @@ fn spawn_zombies(
                     speed: 1.0,
                     health: 60.0,
                     max_health: 60.0,
-                    damage: 20.0,
                     zombie_type: ZombieType::Heavy,
                     ..Zombie::default()
                 };
@@ -169,7 +198,6 @@ fn spawn_zombies(
                     speed: 3.0,
                     health: 10.0,
                     max_health: 10.0,
-                    damage: 50.0,
                     attack_range: 5.0,
                     zombie_type: ZombieType::Exploder,
                     ..Zombie::default()
@@ -188,11 +216,12 @@ fn spawn_zombies(
                     restitution: 0.1,
                     drag: 0.85,
                     is_kinematic: false,
+                    ..default()
                 },
                 Collider {
-                    radius: 0.6,
+                    shape: ColliderShape::Obb { half_extents: Vec3::new(0.5, 1.0, 0.5) },
                     collision_layer: CollisionLayer::Zombie,
-                    collision_mask: CollisionLayer::Player.mask() | CollisionLayer::Zombie.mask() | CollisionLayer::Environment.mask(),
+                    collision_mask: CollisionLayer::Player.mask() | CollisionLayer::Zombie.mask() | CollisionLayer::Bullet.mask() | CollisionLayer::Environment.mask(),
                 },
                 CollisionDamage {
                     damage: 10.0,
@@ -207,11 +236,12 @@ fn spawn_zombies(
                     restitution: 0.2,
                     drag: 0.9,
                     is_kinematic: false,
+                    ..default()
                 },
                 Collider {
-                    radius: 0.5,
+                    shape: ColliderShape::Sphere { radius: 0.5 },
                     collision_layer: CollisionLayer::Zombie,
-                    collision_mask: CollisionLayer::Player.mask() | CollisionLayer::Zombie.mask() | CollisionLayer::Environment.mask(),
+                    collision_mask: CollisionLayer::Player.mask() | CollisionLayer::Zombie.mask() | CollisionLayer::Bullet.mask() | CollisionLayer::Environment.mask(),
                 },
                 CollisionDamage {
                     damage: 5.0,
@@ -226,11 +256,12 @@ fn spawn_zombies(
                     restitution: 0.05,
                     drag: 0.8,
                     is_kinematic: false,
+                    ..default()
                 },
                 Collider {
-                    radius: 0.8,
+                    shape: ColliderShape::Obb { half_extents: Vec3::new(0.75, 1.25, 0.75) },
                     collision_layer: CollisionLayer::Zombie,
-                    collision_mask: CollisionLayer::Player.mask() | CollisionLayer::Zombie.mask() | CollisionLayer::Environment.mask(),
+                    collision_mask: CollisionLayer::Player.mask() | CollisionLayer::Zombie.mask() | CollisionLayer::Bullet.mask() | CollisionLayer::Environment.mask(),
                 },
                 CollisionDamage {
                     damage: 20.0,
@@ -245,11 +276,12 @@ fn spawn_zombies(
                     restitution: 0.3,
                     drag: 0.9,
                     is_kinematic: false,
+                    ..default()
                 },
                 Collider {
-                    radius: 0.4,
+                    shape: ColliderShape::Sphere { radius: 0.4 },
                     collision_layer: CollisionLayer::Zombie,
-                    collision_mask: CollisionLayer::Player.mask() | CollisionLayer::Zombie.mask() | CollisionLayer::Environment.mask(),
+                    collision_mask: CollisionLayer::Player.mask() | CollisionLayer::Zombie.mask() | CollisionLayer::Bullet.mask() | CollisionLayer::Environment.mask(),
                 },
                 CollisionDamage {
                     damage: 50.0,
@@ -275,14 +307,33 @@ fn spawn_zombies(
     }
 }
 
+fn check_wave_complete(
+    zombie_query: Query<(), With<Zombie>>,
+    mut wave: ResMut<ZombieWave>,
+    mut spawn_timer: ResMut<ZombieSpawnTimer>,
+    mut wave_events: EventWriter<WaveCompletedEvent>,
+) {
+    if wave.spawned < wave.to_spawn || !zombie_query.is_empty() {
+        return;
+    }
+
+    wave_events.send(WaveCompletedEvent { wave: wave.number });
+    *wave = ZombieWave::new(wave.number + 1);
+    spawn_timer.timer.reset();
+}
+
 fn zombie_ai(
     player_query: Query<&Transform, (With<Player>, Without<Zombie>)>,
     mut zombie_query: Query<&mut Transform, (With<Zombie>, Without<Player>)>,
 ) {
     if let Ok(player_transform) = player_query.get_single() {
         for mut zombie_transform in zombie_query.iter_mut() {
-            let direction = (player_transform.translation - zombie_transform.translation).normalize();
-            zombie_transform.look_to(direction, Vec3::Y);
+            // Turn on the horizontal plane only, so the tilted box collider can't dig into the floor
+            let mut direction = player_transform.translation - zombie_transform.translation;
+            direction.y = 0.0;
+            if let Some(direction) = direction.try_normalize() {
+                zombie_transform.look_to(direction, Vec3::Y);
+            }
         }
     }
 }
@@ -297,7 +348,10 @@ fn zombie_movement(
             let direction = player_transform.translation - zombie_transform.translation;
             let distance = direction.length();
             
-            if distance > zombie.attack_range && distance > 0.0 {
+            // Melee zombies keep crowding in once in range so the horde can surround and pin the player
+            let presses_in = !matches!(zombie.zombie_type, ZombieType::Exploder);
+            
+            if distance > 0.0 && (distance > zombie.attack_range || presses_in) {
                 // Normalize direction and flatten to horizontal plane
                 let mut move_direction = direction.normalize();
                 move_direction.y = 0.0;
@@ -323,7 +377,7 @@ fn zombie_movement(
                 }
             } else if ground_detector.is_grounded {
                 // Apply stopping force when in attack range
-                let stopping_force = 0.8;
+                let stopping_force = damping_factor(0.8, time.delta_seconds());
                 rigidbody.velocity.x *= stopping_force;
                 rigidbody.velocity.z *= stopping_force;
             }
@@ -331,32 +385,40 @@ fn zombie_movement(
     }
 }
 
-fn zombie_attack(
-    player_query: Query<&Transform, (With<Player>, Without<Zombie>)>,
-    mut zombie_query: Query<(&Transform, &mut Zombie), Without<Player>>,
-    time: Res<Time>,
-) {
-    if let Ok(player_transform) = player_query.get_single() {
-        let current_time = time.elapsed_seconds();
-        
-        for (zombie_transform, mut zombie) in zombie_query.iter_mut() {
-            let distance = zombie_transform.translation.distance(player_transform.translation);
-            
-            if distance <= zombie.attack_range && current_time - zombie.last_attack > 1.0 {
-                zombie.last_attack = current_time;
-                // TODO: Damage player system
-                println!("Zombie attacks for {} damage!", zombie.damage);
-            }
-        }
-    }
-}
-
 fn cleanup_dead_zombies(
     mut commands: Commands,
-    zombie_query: Query<(Entity, &Zombie)>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    zombie_query: Query<(Entity, &Transform, &Zombie, &RigidBody, &Handle<StandardMaterial>)>,
+    debris_query: Query<(), With<Debris>>,
+    debris_settings: Res<DebrisSettings>,
+    mut trauma_events: EventWriter<CameraTraumaEvent>,
 ) {
-    for (entity, zombie) in zombie_query.iter() {
+    let mut active_chunks = debris_query.iter().len();
+
+    for (entity, transform, zombie, rigidbody, material_handle) in zombie_query.iter() {
         if zombie.health <= 0.0 {
+            // Break the body into physics debris instead of vanishing
+            let source_material = materials.get(material_handle).cloned();
+            let lifetime = debris_settings.budgeted_lifetime(active_chunks);
+            active_chunks += spawn_zombie_debris(
+                &mut commands,
+                &mut meshes,
+                &mut materials,
+                &debris_settings,
+                DebrisSource {
+                    transform,
+                    zombie,
+                    body: rigidbody,
+                    material: source_material.as_ref(),
+                },
+                lifetime,
+            );
+
+            trauma_events.send(CameraTraumaEvent {
+                kind: if matches!(zombie.zombie_type, ZombieType::Exploder) { TraumaKind::Explosion } else { TraumaKind::Kill },
+                position: transform.translation,
+            });
             commands.entity(entity).despawn();
         }
     }